@@ -0,0 +1,44 @@
+fn main() {
+    let mut build = cc::Build::new();
+    build.include("vendor/enet/include");
+
+    for file in &[
+        "callbacks",
+        "compress",
+        "host",
+        "list",
+        "packet",
+        "peer",
+        "protocol",
+    ] {
+        build.file(format!("vendor/enet/{}.c", file));
+    }
+
+    if std::env::var("CARGO_CFG_WINDOWS").is_ok() {
+        build.file("vendor/enet/win32.c");
+
+        println!("cargo:rustc-link-lib=ws2_32");
+        println!("cargo:rustc-link-lib=winmm");
+    } else {
+        build.file("vendor/enet/unix.c");
+
+        for define in &[
+            "HAS_FCNTL",
+            "HAS_POLL",
+            "HAS_GETADDRINFO",
+            "HAS_GETNAMEINFO",
+            "HAS_INET_PTON",
+            "HAS_INET_NTOP",
+            "HAS_MSGHDR_FLAGS",
+            "HAS_SOCKLEN_T",
+        ] {
+            build.define(define, "1");
+        }
+    }
+
+    // ENet doesn't compile cleanly with every warning enabled, there's nothing to act on here.
+    build.warnings(false);
+    build.compile("enet");
+
+    println!("cargo:rerun-if-changed=vendor/enet");
+}
@@ -0,0 +1,465 @@
+//! Low level bindings to the ENet vendored in this crate.
+//!
+//! The bindings are written by hand and keep the names bindgen would generate. Structures only used
+//! behind pointers, like `ENetChannel`, are left opaque.
+
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+pub type enet_uint8 = u8;
+pub type enet_uint16 = u16;
+pub type enet_uint32 = c_uint;
+pub type ENetVersion = enet_uint32;
+
+#[cfg(not(windows))]
+pub type ENetSocket = c_int;
+#[cfg(windows)]
+pub type ENetSocket = usize;
+
+#[cfg(not(windows))]
+pub const ENET_SOCKET_NULL: ENetSocket = -1;
+#[cfg(windows)]
+pub const ENET_SOCKET_NULL: ENetSocket = !0;
+
+pub const ENET_PORT_ANY: u32 = 0;
+
+pub type _bindgen_ty_1 = c_uint;
+pub const ENET_PROTOCOL_MINIMUM_MTU: _bindgen_ty_1 = 576;
+pub const ENET_PROTOCOL_MAXIMUM_MTU: _bindgen_ty_1 = 4096;
+pub const ENET_PROTOCOL_MAXIMUM_PACKET_COMMANDS: _bindgen_ty_1 = 32;
+pub const ENET_PROTOCOL_MINIMUM_WINDOW_SIZE: _bindgen_ty_1 = 4096;
+pub const ENET_PROTOCOL_MAXIMUM_WINDOW_SIZE: _bindgen_ty_1 = 65536;
+pub const ENET_PROTOCOL_MINIMUM_CHANNEL_COUNT: _bindgen_ty_1 = 1;
+pub const ENET_PROTOCOL_MAXIMUM_CHANNEL_COUNT: _bindgen_ty_1 = 255;
+pub const ENET_PROTOCOL_MAXIMUM_PEER_ID: _bindgen_ty_1 = 4095;
+pub const ENET_PROTOCOL_MAXIMUM_FRAGMENT_COUNT: _bindgen_ty_1 = 1048576;
+
+pub type _bindgen_ty_2 = c_uint;
+pub const ENET_HOST_RECEIVE_BUFFER_SIZE: _bindgen_ty_2 = 262144;
+pub const ENET_HOST_SEND_BUFFER_SIZE: _bindgen_ty_2 = 262144;
+pub const ENET_HOST_BANDWIDTH_THROTTLE_INTERVAL: _bindgen_ty_2 = 1000;
+pub const ENET_HOST_DEFAULT_MTU: _bindgen_ty_2 = 1392;
+pub const ENET_HOST_DEFAULT_MAXIMUM_PACKET_SIZE: _bindgen_ty_2 = 33554432;
+pub const ENET_HOST_DEFAULT_MAXIMUM_WAITING_DATA: _bindgen_ty_2 = 33554432;
+pub const ENET_PEER_DEFAULT_ROUND_TRIP_TIME: _bindgen_ty_2 = 500;
+pub const ENET_PEER_DEFAULT_PACKET_THROTTLE: _bindgen_ty_2 = 32;
+pub const ENET_PEER_PACKET_THROTTLE_SCALE: _bindgen_ty_2 = 32;
+pub const ENET_PEER_PACKET_THROTTLE_COUNTER: _bindgen_ty_2 = 7;
+pub const ENET_PEER_PACKET_THROTTLE_ACCELERATION: _bindgen_ty_2 = 2;
+pub const ENET_PEER_PACKET_THROTTLE_DECELERATION: _bindgen_ty_2 = 2;
+pub const ENET_PEER_PACKET_THROTTLE_INTERVAL: _bindgen_ty_2 = 5000;
+pub const ENET_PEER_PACKET_LOSS_SCALE: _bindgen_ty_2 = 65536;
+pub const ENET_PEER_PACKET_LOSS_INTERVAL: _bindgen_ty_2 = 10000;
+pub const ENET_PEER_WINDOW_SIZE_SCALE: _bindgen_ty_2 = 65536;
+pub const ENET_PEER_TIMEOUT_LIMIT: _bindgen_ty_2 = 32;
+pub const ENET_PEER_TIMEOUT_MINIMUM: _bindgen_ty_2 = 5000;
+pub const ENET_PEER_TIMEOUT_MAXIMUM: _bindgen_ty_2 = 30000;
+pub const ENET_PEER_PING_INTERVAL: _bindgen_ty_2 = 500;
+pub const ENET_PEER_UNSEQUENCED_WINDOWS: _bindgen_ty_2 = 64;
+pub const ENET_PEER_UNSEQUENCED_WINDOW_SIZE: _bindgen_ty_2 = 1024;
+pub const ENET_PEER_FREE_UNSEQUENCED_WINDOWS: _bindgen_ty_2 = 32;
+pub const ENET_PEER_RELIABLE_WINDOWS: _bindgen_ty_2 = 16;
+pub const ENET_PEER_RELIABLE_WINDOW_SIZE: _bindgen_ty_2 = 4096;
+pub const ENET_PEER_FREE_RELIABLE_WINDOWS: _bindgen_ty_2 = 8;
+
+pub type _ENetPacketFlag = c_uint;
+pub const _ENetPacketFlag_ENET_PACKET_FLAG_RELIABLE: _ENetPacketFlag = 1;
+pub const _ENetPacketFlag_ENET_PACKET_FLAG_UNSEQUENCED: _ENetPacketFlag = 2;
+pub const _ENetPacketFlag_ENET_PACKET_FLAG_NO_ALLOCATE: _ENetPacketFlag = 4;
+pub const _ENetPacketFlag_ENET_PACKET_FLAG_UNRELIABLE_FRAGMENT: _ENetPacketFlag = 8;
+pub const _ENetPacketFlag_ENET_PACKET_FLAG_SENT: _ENetPacketFlag = 256;
+pub use self::_ENetPacketFlag as ENetPacketFlag;
+
+pub type _ENetPeerState = c_uint;
+pub const _ENetPeerState_ENET_PEER_STATE_DISCONNECTED: _ENetPeerState = 0;
+pub const _ENetPeerState_ENET_PEER_STATE_CONNECTING: _ENetPeerState = 1;
+pub const _ENetPeerState_ENET_PEER_STATE_ACKNOWLEDGING_CONNECT: _ENetPeerState = 2;
+pub const _ENetPeerState_ENET_PEER_STATE_CONNECTION_PENDING: _ENetPeerState = 3;
+pub const _ENetPeerState_ENET_PEER_STATE_CONNECTION_SUCCEEDED: _ENetPeerState = 4;
+pub const _ENetPeerState_ENET_PEER_STATE_CONNECTED: _ENetPeerState = 5;
+pub const _ENetPeerState_ENET_PEER_STATE_DISCONNECT_LATER: _ENetPeerState = 6;
+pub const _ENetPeerState_ENET_PEER_STATE_DISCONNECTING: _ENetPeerState = 7;
+pub const _ENetPeerState_ENET_PEER_STATE_ACKNOWLEDGING_DISCONNECT: _ENetPeerState = 8;
+pub const _ENetPeerState_ENET_PEER_STATE_ZOMBIE: _ENetPeerState = 9;
+pub use self::_ENetPeerState as ENetPeerState;
+
+pub type _ENetEventType = c_uint;
+pub const _ENetEventType_ENET_EVENT_TYPE_NONE: _ENetEventType = 0;
+pub const _ENetEventType_ENET_EVENT_TYPE_CONNECT: _ENetEventType = 1;
+pub const _ENetEventType_ENET_EVENT_TYPE_DISCONNECT: _ENetEventType = 2;
+pub const _ENetEventType_ENET_EVENT_TYPE_RECEIVE: _ENetEventType = 3;
+pub use self::_ENetEventType as ENetEventType;
+
+pub type _ENetSocketWait = c_uint;
+pub const _ENetSocketWait_ENET_SOCKET_WAIT_NONE: _ENetSocketWait = 0;
+pub const _ENetSocketWait_ENET_SOCKET_WAIT_SEND: _ENetSocketWait = 1;
+pub const _ENetSocketWait_ENET_SOCKET_WAIT_RECEIVE: _ENetSocketWait = 2;
+pub const _ENetSocketWait_ENET_SOCKET_WAIT_INTERRUPT: _ENetSocketWait = 4;
+
+/// IPv6 host address in network byte order, IPv4 addresses are stored IPv4-mapped.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct _ENetHostAddress {
+    pub addr: [enet_uint8; 16usize],
+}
+pub type ENetHostAddress = _ENetHostAddress;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetAddress {
+    pub host: ENetHostAddress,
+    pub scopeID: enet_uint32,
+    pub port: enet_uint16,
+}
+pub type ENetAddress = _ENetAddress;
+
+extern "C" {
+    pub static ENET_HOST_ANY: ENetHostAddress;
+    pub static ENET_HOST_BROADCAST: ENetHostAddress;
+}
+
+#[cfg(not(windows))]
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ENetBuffer {
+    pub data: *mut c_void,
+    pub dataLength: usize,
+}
+
+#[cfg(windows)]
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ENetBuffer {
+    pub dataLength: usize,
+    pub data: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetPacket {
+    pub referenceCount: usize,
+    pub flags: enet_uint32,
+    pub data: *mut enet_uint8,
+    pub dataLength: usize,
+    pub freeCallback: ENetPacketFreeCallback,
+    pub userData: *mut c_void,
+}
+pub type ENetPacket = _ENetPacket;
+
+pub type ENetPacketFreeCallback = Option<unsafe extern "C" fn(arg1: *mut _ENetPacket)>;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetListNode {
+    pub next: *mut _ENetListNode,
+    pub previous: *mut _ENetListNode,
+}
+pub type ENetListNode = _ENetListNode;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetList {
+    pub sentinel: ENetListNode,
+}
+pub type ENetList = _ENetList;
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct _ENetProtocol {
+    _opaque: [u8; 48],
+}
+pub type ENetProtocol = _ENetProtocol;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetChannel {
+    _opaque: [u8; 0],
+}
+pub type ENetChannel = _ENetChannel;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetPeer {
+    pub dispatchList: ENetListNode,
+    pub host: *mut _ENetHost,
+    pub outgoingPeerID: enet_uint16,
+    pub incomingPeerID: enet_uint16,
+    pub connectID: enet_uint32,
+    pub outgoingSessionID: enet_uint8,
+    pub incomingSessionID: enet_uint8,
+    pub address: ENetAddress,
+    pub data: *mut c_void,
+    pub state: ENetPeerState,
+    pub channels: *mut ENetChannel,
+    pub channelCount: usize,
+    pub incomingBandwidth: enet_uint32,
+    pub outgoingBandwidth: enet_uint32,
+    pub incomingBandwidthThrottleEpoch: enet_uint32,
+    pub outgoingBandwidthThrottleEpoch: enet_uint32,
+    pub incomingDataTotal: enet_uint32,
+    pub outgoingDataTotal: enet_uint32,
+    pub lastSendTime: enet_uint32,
+    pub lastReceiveTime: enet_uint32,
+    pub nextTimeout: enet_uint32,
+    pub earliestTimeout: enet_uint32,
+    pub packetLossEpoch: enet_uint32,
+    pub packetsSent: enet_uint32,
+    pub packetsLost: enet_uint32,
+    pub packetLoss: enet_uint32,
+    pub packetLossVariance: enet_uint32,
+    pub packetThrottle: enet_uint32,
+    pub packetThrottleLimit: enet_uint32,
+    pub packetThrottleCounter: enet_uint32,
+    pub packetThrottleEpoch: enet_uint32,
+    pub packetThrottleAcceleration: enet_uint32,
+    pub packetThrottleDeceleration: enet_uint32,
+    pub packetThrottleInterval: enet_uint32,
+    pub pingInterval: enet_uint32,
+    pub timeoutLimit: enet_uint32,
+    pub timeoutMinimum: enet_uint32,
+    pub timeoutMaximum: enet_uint32,
+    pub lastRoundTripTime: enet_uint32,
+    pub lowestRoundTripTime: enet_uint32,
+    pub lastRoundTripTimeVariance: enet_uint32,
+    pub highestRoundTripTimeVariance: enet_uint32,
+    pub roundTripTime: enet_uint32,
+    pub roundTripTimeVariance: enet_uint32,
+    pub mtu: enet_uint32,
+    pub windowSize: enet_uint32,
+    pub reliableDataInTransit: enet_uint32,
+    pub outgoingReliableSequenceNumber: enet_uint16,
+    pub acknowledgements: ENetList,
+    pub sentReliableCommands: ENetList,
+    pub outgoingSendReliableCommands: ENetList,
+    pub outgoingCommands: ENetList,
+    pub dispatchedCommands: ENetList,
+    pub flags: enet_uint16,
+    pub reserved: enet_uint16,
+    pub incomingUnsequencedGroup: enet_uint16,
+    pub outgoingUnsequencedGroup: enet_uint16,
+    pub unsequencedWindow: [enet_uint32; 32usize],
+    pub eventData: enet_uint32,
+    pub totalWaitingData: usize,
+}
+pub type ENetPeer = _ENetPeer;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetCompressor {
+    pub context: *mut c_void,
+    pub compress: Option<
+        unsafe extern "C" fn(
+            context: *mut c_void,
+            inBuffers: *const ENetBuffer,
+            inBufferCount: usize,
+            inLimit: usize,
+            outData: *mut enet_uint8,
+            outLimit: usize,
+        ) -> usize,
+    >,
+    pub decompress: Option<
+        unsafe extern "C" fn(
+            context: *mut c_void,
+            inData: *const enet_uint8,
+            inLimit: usize,
+            outData: *mut enet_uint8,
+            outLimit: usize,
+        ) -> usize,
+    >,
+    pub destroy: Option<unsafe extern "C" fn(context: *mut c_void)>,
+}
+pub type ENetCompressor = _ENetCompressor;
+
+pub type ENetChecksumCallback =
+    Option<unsafe extern "C" fn(buffers: *const ENetBuffer, bufferCount: usize) -> enet_uint32>;
+pub type ENetInterceptCallback =
+    Option<unsafe extern "C" fn(host: *mut _ENetHost, event: *mut _ENetEvent) -> c_int>;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetHost {
+    pub socket: ENetSocket,
+    pub address: ENetAddress,
+    pub incomingBandwidth: enet_uint32,
+    pub outgoingBandwidth: enet_uint32,
+    pub bandwidthThrottleEpoch: enet_uint32,
+    pub mtu: enet_uint32,
+    pub randomSeed: enet_uint32,
+    pub recalculateBandwidthLimits: c_int,
+    pub peers: *mut ENetPeer,
+    pub peerCount: usize,
+    pub channelLimit: usize,
+    pub serviceTime: enet_uint32,
+    pub dispatchQueue: ENetList,
+    pub totalQueued: enet_uint32,
+    pub packetSize: usize,
+    pub headerFlags: enet_uint16,
+    pub commands: [ENetProtocol; 32usize],
+    pub commandCount: usize,
+    pub buffers: [ENetBuffer; 65usize],
+    pub bufferCount: usize,
+    pub checksum: ENetChecksumCallback,
+    pub compressor: ENetCompressor,
+    pub packetData: [[enet_uint8; 4096usize]; 2usize],
+    pub receivedAddress: ENetAddress,
+    pub receivedData: *mut enet_uint8,
+    pub receivedDataLength: usize,
+    pub totalSentData: enet_uint32,
+    pub totalSentPackets: enet_uint32,
+    pub totalReceivedData: enet_uint32,
+    pub totalReceivedPackets: enet_uint32,
+    pub intercept: ENetInterceptCallback,
+    pub connectedPeers: usize,
+    pub bandwidthLimitedPeers: usize,
+    pub duplicatePeers: usize,
+    pub maximumPacketSize: usize,
+    pub maximumWaitingData: usize,
+}
+pub type ENetHost = _ENetHost;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct _ENetEvent {
+    pub type_: ENetEventType,
+    pub peer: *mut ENetPeer,
+    pub channelID: enet_uint8,
+    pub data: enet_uint32,
+    pub packet: *mut ENetPacket,
+}
+pub type ENetEvent = _ENetEvent;
+
+// The layout as compiled by C on 64 bit Unix, to catch mistakes in the declarations above.
+#[cfg(all(unix, target_pointer_width = "64"))]
+const _: () = {
+    use std::mem::{offset_of, size_of};
+
+    assert!(size_of::<ENetAddress>() == 24);
+    assert!(size_of::<ENetPacket>() == 48);
+    assert!(size_of::<ENetPeer>() == 472);
+    assert!(offset_of!(ENetPeer, data) == 64);
+    assert!(offset_of!(ENetPeer, unsequencedWindow) == 328);
+    assert!(size_of::<ENetHost>() == 11056);
+    assert!(offset_of!(ENetHost, checksum) == 2720);
+    assert!(offset_of!(ENetHost, receivedAddress) == 10952);
+    assert!(offset_of!(ENetHost, maximumWaitingData) == 11048);
+    assert!(size_of::<ENetEvent>() == 32);
+};
+
+extern "C" {
+    pub fn enet_protocol_command_size(arg1: enet_uint8) -> usize;
+    pub fn enet_initialize() -> c_int;
+    pub fn enet_deinitialize();
+    pub fn enet_linked_version() -> ENetVersion;
+    pub fn enet_time_get() -> enet_uint32;
+    pub fn enet_time_set(arg1: enet_uint32);
+    pub fn enet_socket_send(
+        arg1: ENetSocket,
+        arg2: *const ENetAddress,
+        arg3: *const ENetBuffer,
+        arg4: usize,
+    ) -> c_int;
+    pub fn enet_socket_wait(arg1: ENetSocket, arg2: *mut enet_uint32, arg3: enet_uint32) -> c_int;
+    pub fn enet_socket_get_address(arg1: ENetSocket, arg2: *mut ENetAddress) -> c_int;
+    pub fn enet_address_set_host_ip(address: *mut ENetAddress, hostName: *const c_char) -> c_int;
+    pub fn enet_address_set_host(address: *mut ENetAddress, hostName: *const c_char) -> c_int;
+    pub fn enet_packet_create(
+        arg1: *const c_void,
+        arg2: usize,
+        arg3: enet_uint32,
+    ) -> *mut ENetPacket;
+    pub fn enet_packet_destroy(arg1: *mut ENetPacket);
+    pub fn enet_packet_resize(arg1: *mut ENetPacket, arg2: usize) -> c_int;
+    pub fn enet_crc32(arg1: *const ENetBuffer, arg2: usize) -> enet_uint32;
+    pub fn enet_host_create(
+        arg1: *const ENetAddress,
+        arg2: usize,
+        arg3: usize,
+        arg4: enet_uint32,
+        arg5: enet_uint32,
+    ) -> *mut ENetHost;
+    pub fn enet_host_destroy(arg1: *mut ENetHost);
+    pub fn enet_host_connect(
+        arg1: *mut ENetHost,
+        arg2: *const ENetAddress,
+        arg3: usize,
+        arg4: enet_uint32,
+    ) -> *mut ENetPeer;
+    pub fn enet_host_check_events(arg1: *mut ENetHost, arg2: *mut ENetEvent) -> c_int;
+    pub fn enet_host_service(arg1: *mut ENetHost, arg2: *mut ENetEvent, arg3: enet_uint32)
+        -> c_int;
+    pub fn enet_host_flush(arg1: *mut ENetHost);
+    pub fn enet_host_broadcast(arg1: *mut ENetHost, arg2: enet_uint8, arg3: *mut ENetPacket);
+    pub fn enet_host_compress(arg1: *mut ENetHost, arg2: *const ENetCompressor);
+    pub fn enet_host_compress_with_range_coder(host: *mut ENetHost) -> c_int;
+    pub fn enet_range_coder_create() -> *mut c_void;
+    pub fn enet_range_coder_destroy(arg1: *mut c_void);
+    pub fn enet_range_coder_compress(
+        arg1: *mut c_void,
+        arg2: *const ENetBuffer,
+        arg3: usize,
+        arg4: usize,
+        arg5: *mut enet_uint8,
+        arg6: usize,
+    ) -> usize;
+    pub fn enet_range_coder_decompress(
+        arg1: *mut c_void,
+        arg2: *const enet_uint8,
+        arg3: usize,
+        arg4: *mut enet_uint8,
+        arg5: usize,
+    ) -> usize;
+    pub fn enet_host_channel_limit(arg1: *mut ENetHost, arg2: usize);
+    pub fn enet_host_bandwidth_limit(arg1: *mut ENetHost, arg2: enet_uint32, arg3: enet_uint32);
+    pub fn enet_host_bandwidth_throttle(arg1: *mut ENetHost);
+    pub fn enet_peer_send(arg1: *mut ENetPeer, arg2: enet_uint8, arg3: *mut ENetPacket) -> c_int;
+    pub fn enet_peer_receive(arg1: *mut ENetPeer, channelID: *mut enet_uint8) -> *mut ENetPacket;
+    pub fn enet_peer_ping(arg1: *mut ENetPeer);
+    pub fn enet_peer_ping_interval(arg1: *mut ENetPeer, arg2: enet_uint32);
+    pub fn enet_peer_timeout(
+        arg1: *mut ENetPeer,
+        arg2: enet_uint32,
+        arg3: enet_uint32,
+        arg4: enet_uint32,
+    );
+    pub fn enet_peer_reset(arg1: *mut ENetPeer);
+    pub fn enet_peer_disconnect(arg1: *mut ENetPeer, arg2: enet_uint32);
+    pub fn enet_peer_disconnect_now(arg1: *mut ENetPeer, arg2: enet_uint32);
+    pub fn enet_peer_disconnect_later(arg1: *mut ENetPeer, arg2: enet_uint32);
+    pub fn enet_peer_throttle_configure(
+        arg1: *mut ENetPeer,
+        arg2: enet_uint32,
+        arg3: enet_uint32,
+        arg4: enet_uint32,
+    );
+}
+
+pub type _ENetProtocolCommand = c_uint;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_NONE: _ENetProtocolCommand = 0;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_ACKNOWLEDGE: _ENetProtocolCommand = 1;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_CONNECT: _ENetProtocolCommand = 2;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_VERIFY_CONNECT: _ENetProtocolCommand = 3;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_DISCONNECT: _ENetProtocolCommand = 4;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_PING: _ENetProtocolCommand = 5;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_RELIABLE: _ENetProtocolCommand = 6;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNRELIABLE: _ENetProtocolCommand = 7;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_FRAGMENT: _ENetProtocolCommand = 8;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNSEQUENCED: _ENetProtocolCommand = 9;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_BANDWIDTH_LIMIT: _ENetProtocolCommand = 10;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_THROTTLE_CONFIGURE: _ENetProtocolCommand = 11;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNRELIABLE_FRAGMENT:
+    _ENetProtocolCommand = 12;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_COUNT: _ENetProtocolCommand = 13;
+pub const _ENetProtocolCommand_ENET_PROTOCOL_COMMAND_MASK: _ENetProtocolCommand = 15;
+pub use self::_ENetProtocolCommand as ENetProtocolCommand;
+pub type _ENetProtocolFlag = c_uint;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_COMMAND_FLAG_ACKNOWLEDGE: _ENetProtocolFlag = 128;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_COMMAND_FLAG_UNSEQUENCED: _ENetProtocolFlag = 64;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_COMPRESSED: _ENetProtocolFlag = 16384;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_SENT_TIME: _ENetProtocolFlag = 32768;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_MASK: _ENetProtocolFlag = 49152;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_HEADER_SESSION_MASK: _ENetProtocolFlag = 12288;
+pub const _ENetProtocolFlag_ENET_PROTOCOL_HEADER_SESSION_SHIFT: _ENetProtocolFlag = 12;
+pub use self::_ENetProtocolFlag as ENetProtocolFlag;
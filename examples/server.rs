@@ -33,13 +33,14 @@ fn run() -> Result<(), Error> {
 
         let desc = match kind {
             EventKind::Connect(data) => format!("connected (data: {:08X})", data),
-            EventKind::Disconnect(data) => format!("disconnected (data: {:08X})", data),
+            EventKind::Disconnect(reason) => format!("disconnected ({:?})", reason),
             EventKind::Receive(packet) => {
                 format!(
                     "wants to say something: {:?}",
                     String::from_utf8_lossy(packet.data())
                 )
             }
+            EventKind::DataAvailable => unreachable!("receives aren't queued"),
         };
 
         println!(
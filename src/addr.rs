@@ -0,0 +1,28 @@
+use enet_sys::{ENetAddress, ENetHostAddress};
+use std::net::{Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+/// Converts a socket address into the representation used by ENet.
+///
+/// ENet stores IPv4 addresses IPv4-mapped (`::ffff:a.b.c.d`), its sockets being dual-stack.
+pub fn to_enet(addr: SocketAddr) -> ENetAddress {
+    let (ip, scope_id) = match addr {
+        SocketAddr::V4(addr) => (addr.ip().to_ipv6_mapped(), 0),
+        SocketAddr::V6(addr) => (*addr.ip(), addr.scope_id()),
+    };
+
+    ENetAddress {
+        host: ENetHostAddress { addr: ip.octets() },
+        scopeID: scope_id,
+        port: addr.port(),
+    }
+}
+
+/// Converts an ENet address into a socket address, IPv4-mapped addresses become IPv4 ones.
+pub fn from_enet(addr: &ENetAddress) -> SocketAddr {
+    let ip = Ipv6Addr::from(addr.host.addr);
+
+    match ip.to_ipv4_mapped() {
+        Some(ip) => SocketAddrV4::new(ip, addr.port).into(),
+        None => SocketAddrV6::new(ip, addr.port, 0, addr.scopeID).into(),
+    }
+}
@@ -0,0 +1,205 @@
+//! Asynchronous driver for hosts, integrating with the [tokio](https://tokio.rs) runtime.
+//!
+//! Only available on Unix platforms with the `tokio` feature enabled.
+
+use crate::error::Error;
+use crate::event::{EventKind, OwnedEvent};
+use crate::host::Host;
+use crate::peer::PeerId;
+
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::ToSocketAddrs;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+use tokio::time::{self, Interval, MissedTickBehavior};
+
+/// Default interval in which the host is serviced even if no data arrives, see [`AsyncHost::with_service_interval`].
+pub const SERVICE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A [`Host`] driven by the tokio reactor instead of blocking in [`Host::service`].
+///
+/// Events are received through the [`Stream`] implementation.
+pub struct AsyncHost<T> {
+    // Order is important here.
+    // The socket has to be deregistered from the reactor before the host closes it.
+    socket: AsyncFd<Socket>,
+    interval: Interval,
+    events: VecDeque<OwnedEvent>,
+    host: Host<T>,
+}
+
+impl<T> AsyncHost<T> {
+    /// Wraps a host, servicing it every [`SERVICE_INTERVAL`] in addition to whenever data arrives.
+    ///
+    /// Has to be called from within a tokio runtime with both IO and time enabled.
+    pub fn new(host: Host<T>) -> Result<Self, Error> {
+        Self::with_service_interval(host, SERVICE_INTERVAL)
+    }
+
+    /// Wraps a host, servicing it every `interval` in addition to whenever data arrives.
+    ///
+    /// ENet resends lost packets, pings peers and detects timeouts only while being serviced, so the interval shouldn't be too long.
+    /// Has to be called from within a tokio runtime with both IO and time enabled.
+    pub fn with_service_interval(host: Host<T>, interval: Duration) -> Result<Self, Error> {
+        if interval.is_zero() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let socket = AsyncFd::with_interest(Socket(host.as_raw_fd()), Interest::READABLE)?;
+
+        let mut interval = time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Ok(Self {
+            socket,
+            interval,
+            events: VecDeque::new(),
+            host,
+        })
+    }
+
+    /// Initiates a connection to a foreign host, see [`Host::connect`].
+    ///
+    /// The returned future resolves once the [`EventKind::Connect`] event for the peer arrives,
+    /// or fails with [`Error::ConnectionFailed`] if the peer disconnects before that.
+    /// Events are still delivered through the [`Stream`] implementation as usual, including the ones for this peer.
+    pub fn connect(
+        &mut self,
+        addrs: impl ToSocketAddrs,
+        channel_count: usize,
+        data: u32,
+        value: T,
+    ) -> Result<Connect<'_, T>, Error> {
+        let peer = self.host.connect(addrs, channel_count, data, value)?.id();
+
+        Ok(Connect {
+            checked: self.events.len(),
+            host: self,
+            peer,
+        })
+    }
+
+    /// Services the host once, queueing the event produced, if any.
+    ///
+    /// Returns `Poll::Pending` if there was nothing to do.
+    fn poll_service(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
+        loop {
+            if let Some(event) = self.host.service_nonblocking()? {
+                self.events.push_back(event.into());
+                return Poll::Ready(Ok(()));
+            }
+
+            // Nothing to process right now, wait for data or the next periodic service.
+            match self.socket.poll_read_ready(cx) {
+                Poll::Ready(Ok(mut guard)) => {
+                    guard.clear_ready();
+                    continue;
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending => {}
+            }
+
+            if self.interval.poll_tick(cx).is_ready() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+}
+
+impl<T> AsyncHost<T> {
+    /// Returns a reference to the wrapped host.
+    pub fn get_ref(&self) -> &Host<T> {
+        &self.host
+    }
+
+    /// Returns a mutable reference to the wrapped host.
+    ///
+    /// Packets queued through the host are sent during the next periodic service, call [`Host::flush`] to send them immediately.
+    pub fn get_mut(&mut self) -> &mut Host<T> {
+        &mut self.host
+    }
+
+    /// Deregisters the host from the reactor and returns it.
+    ///
+    /// Events that have already been received but not yet consumed from the stream are lost.
+    pub fn into_inner(self) -> Host<T> {
+        let Self { socket, host, .. } = self;
+        socket.into_inner();
+
+        host
+    }
+}
+
+// The host is never pinned structurally.
+impl<T> Unpin for AsyncHost<T> {}
+
+impl<T> Stream for AsyncHost<T> {
+    type Item = Result<OwnedEvent, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(event) = this.events.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            if let Err(err) = futures_core::ready!(this.poll_service(cx)) {
+                return Poll::Ready(Some(Err(err)));
+            }
+        }
+    }
+}
+
+/// Future returned by [`AsyncHost::connect`].
+pub struct Connect<'a, T> {
+    host: &'a mut AsyncHost<T>,
+    peer: PeerId,
+    // Number of queued events that have already been looked at.
+    checked: usize,
+}
+
+impl<T> Future for Connect<'_, T> {
+    type Output = Result<PeerId, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            for event in this.host.events.iter().skip(this.checked) {
+                this.checked += 1;
+
+                if event.peer != this.peer {
+                    continue;
+                }
+
+                match event.kind {
+                    EventKind::Connect(_) => return Poll::Ready(Ok(this.peer)),
+                    EventKind::Disconnect(_) => return Poll::Ready(Err(Error::ConnectionFailed)),
+                    EventKind::Receive(_) | EventKind::DataAvailable => {}
+                }
+            }
+
+            futures_core::ready!(this.host.poll_service(cx))?;
+        }
+    }
+}
+
+/// Socket of a host, registered with the reactor.
+///
+/// Owned by the host rather than this type.
+struct Socket(RawFd);
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
@@ -0,0 +1,57 @@
+//! Named channels with a fixed delivery policy.
+//!
+//! A host built with [`HostBuilder::channel`](crate::host::HostBuilder::channel) knows what every channel is used for,
+//! so packets can be sent with [`PeerMut::send_on`](crate::peer::PeerMut::send_on) without choosing the flags by hand.
+
+use crate::packet::Flags;
+
+/// How packets sent on a channel are delivered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Delivery {
+    /// Packets are resent until they arrive and are received in the order they were sent.
+    ReliableOrdered,
+    /// Packets may be lost, but are never received out of order.
+    UnreliableSequenced,
+    /// Packets may be lost or received in any order.
+    Unsequenced,
+    /// Like [`Delivery::UnreliableSequenced`], but packets exceeding the MTU are fragmented using unreliable sends as well.
+    UnreliableFragment,
+}
+
+impl Delivery {
+    /// Returns the packet flags implementing this policy.
+    pub fn flags(self) -> Flags {
+        let flags = Flags::default();
+
+        match self {
+            Self::ReliableOrdered => flags.reliable(),
+            Self::UnreliableSequenced => flags,
+            Self::Unsequenced => flags.unsequenced(),
+            Self::UnreliableFragment => flags.unreliable_fragment(),
+        }
+    }
+}
+
+/// A channel of a host, obtained through [`Host::channel`](crate::host::Host::channel).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Channel {
+    id: u8,
+    delivery: Delivery,
+}
+
+impl Channel {
+    /// Describes a channel without looking it up, e.g. for use in constants.
+    ///
+    /// The ID has to match the position of the channel in the host's schema.
+    pub const fn new(id: u8, delivery: Delivery) -> Self {
+        Self { id, delivery }
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    pub fn delivery(&self) -> Delivery {
+        self.delivery
+    }
+}
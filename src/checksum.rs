@@ -0,0 +1,172 @@
+//! Packet checksums for detecting corrupted datagrams.
+
+use crate::compress::InputBuffer;
+use crate::protocol;
+use crate::scope;
+
+use enet_sys::{ENetBuffer, ENetHost};
+use libc::size_t;
+use std::any::Any;
+use std::cell::Cell;
+use std::convert::TryInto;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
+
+pub trait Checksum {
+    /// Compute the checksum of the concatenation of input buffers.
+    ///
+    /// Both ends of a connection have to use the same checksum.
+    /// ENet computes it once for every datagram it sends or receives, this crate never invokes it on its own.
+    fn checksum(&mut self, input_buffers: &[InputBuffer]) -> u32;
+}
+
+/// The CRC32 checksum built into ENet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Crc32;
+
+impl Checksum for Crc32 {
+    fn checksum(&mut self, input_buffers: &[InputBuffer]) -> u32 {
+        unsafe {
+            enet_sys::enet_crc32(
+                input_buffers.as_ptr() as *const ENetBuffer,
+                input_buffers.len(),
+            )
+        }
+    }
+}
+
+pub(crate) struct ChecksumCtx {
+    pub(crate) checksum: Box<dyn Checksum>,
+    pub(crate) panic: Option<Box<dyn Any + Send>>,
+    pub(crate) mismatches: u64,
+    host: *mut ENetHost,
+    // Checksum of the datagram currently being received, as sent by the remote host.
+    received: Option<u32>,
+}
+
+impl ChecksumCtx {
+    pub(crate) fn new(checksum: Box<dyn Checksum>) -> Self {
+        Self {
+            checksum,
+            panic: None,
+            mismatches: 0,
+            host: ptr::null_mut(),
+            received: None,
+        }
+    }
+}
+
+/// Contexts of hosts using a checksum, keyed by the host pointer.
+///
+/// Needed for calls into ENet that may send data but are made through a peer rather than a [`Host`](crate::host::Host).
+static CONTEXTS: Mutex<Vec<(usize, usize)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CURRENT: Cell<*mut ChecksumCtx> = const { Cell::new(ptr::null_mut()) };
+}
+
+pub(crate) type Scope = scope::Scope<ChecksumCtx>;
+
+/// Installs the checksum callback on a host.
+///
+/// The context has to be unregistered before it's dropped.
+pub(crate) unsafe fn register(host: *mut ENetHost, ctx: *mut ChecksumCtx) {
+    (*ctx).host = host;
+    (*host).checksum = Some(checksum);
+
+    CONTEXTS.lock().unwrap().push((host as usize, ctx as usize));
+}
+
+pub(crate) fn unregister(host: *mut ENetHost) {
+    CONTEXTS
+        .lock()
+        .unwrap()
+        .retain(|(registered, _)| *registered != host as usize);
+}
+
+/// Makes the checksum context available to the callbacks until the returned guard is dropped.
+pub(crate) fn enter(ctx: *mut ChecksumCtx) -> Scope {
+    scope::enter(&CURRENT, ctx)
+}
+
+/// Like [`enter`], but looks up the context of a host, if it uses a checksum at all.
+pub(crate) unsafe fn enter_host(host: *mut ENetHost) -> Option<Scope> {
+    // Hosts without a checksum are never registered, so they don't need to wait for the lock.
+    (*host).checksum?;
+
+    let ctx = CONTEXTS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(registered, _)| *registered == host as usize)
+        .map(|(_, ctx)| *ctx as *mut ChecksumCtx)?;
+
+    Some(enter(ctx))
+}
+
+unsafe extern "C" fn checksum(buffers: *const ENetBuffer, buffer_count: size_t) -> u32 {
+    let ctx = scope::get(&CURRENT);
+    if ctx.is_null() {
+        // Never happens, all calls into ENet that may send or receive data are made within a scope.
+        return 0;
+    }
+
+    let ctx = &mut *ctx;
+    let input_buffers = slice::from_raw_parts(buffers as *const InputBuffer, buffer_count);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| ctx.checksum.checksum(input_buffers)));
+
+    let value = match result {
+        Ok(value) => value,
+        Err(err) => {
+            ctx.panic = Some(err);
+            return 0;
+        }
+    };
+
+    // ENet verifies received datagrams by passing their contents as the only buffer.
+    if buffer_count == 1 && (*buffers).data as *const u8 == (*ctx.host).receivedData {
+        if let Some(received) = ctx.received.take() {
+            if received != value {
+                ctx.mismatches += 1;
+            }
+
+            protocol::note_checksum(received == value);
+        }
+    }
+
+    value
+}
+
+/// Remembers the checksum of a datagram that was just received, called from the intercept callback.
+///
+/// ENet overwrites the checksum of a received datagram before verifying it, so it has to be remembered beforehand.
+pub(crate) unsafe fn save_received(host: *mut ENetHost) {
+    let ctx = scope::get(&CURRENT);
+    if ctx.is_null() {
+        return;
+    }
+
+    let host = &*host;
+    let data = slice::from_raw_parts(host.receivedData, host.receivedDataLength);
+
+    (*ctx).received = received_checksum(data);
+}
+
+/// Extracts the checksum from the protocol header of a datagram.
+fn received_checksum(data: &[u8]) -> Option<u32> {
+    let peer_id = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+
+    let offset =
+        if peer_id & enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_SENT_TIME as u16 != 0 {
+            4
+        } else {
+            2
+        };
+
+    let checksum = data.get(offset..offset + 4)?;
+
+    Some(u32::from_ne_bytes(checksum.try_into().unwrap()))
+}
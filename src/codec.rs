@@ -0,0 +1,84 @@
+//! Serialization formats used for sending serde messages in packets.
+//!
+//! [`Bincode`] is used by [`Packet::from_message`](crate::packet::Packet::from_message) and [`Packet::decode`](crate::packet::Packet::decode),
+//! other formats can be used through their `_with` counterparts.
+
+use crate::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+pub trait Codec {
+    /// Serializes a message into bytes.
+    fn encode<T>(&self, message: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized;
+
+    /// Deserializes a message from bytes.
+    fn decode<'de, T>(&self, data: &'de [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'de>;
+}
+
+/// The [bincode](https://docs.rs/bincode) format, compact and fast.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bincode;
+
+impl Codec for Bincode {
+    fn encode<T>(&self, message: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        bincode::serialize(message).map_err(|err| Error::Encode(err))
+    }
+
+    fn decode<'de, T>(&self, data: &'de [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        bincode::deserialize(data).map_err(|err| Error::Decode(err))
+    }
+}
+
+/// The [postcard](https://docs.rs/postcard) format, which encodes integers as varints.
+#[cfg(feature = "postcard")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl Codec for Postcard {
+    fn encode<T>(&self, message: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        postcard::to_allocvec(message).map_err(|err| Error::Encode(Box::new(err)))
+    }
+
+    fn decode<'de, T>(&self, data: &'de [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        postcard::from_bytes(data).map_err(|err| Error::Decode(Box::new(err)))
+    }
+}
+
+/// The JSON format, useful for debugging or talking to peers not written in Rust.
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Codec for Json {
+    fn encode<T>(&self, message: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        serde_json::to_vec(message).map_err(|err| Error::Encode(Box::new(err)))
+    }
+
+    fn decode<'de, T>(&self, data: &'de [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        serde_json::from_slice(data).map_err(|err| Error::Decode(Box::new(err)))
+    }
+}
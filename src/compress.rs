@@ -1,6 +1,7 @@
 use enet_sys::ENetBuffer;
 use std::fmt::{self, Debug, Formatter};
 use std::io::{self, ErrorKind, Write};
+use std::ptr;
 use std::slice;
 
 /// Generic packet (de)compression error.
@@ -16,6 +17,10 @@ impl From<io::Error> for Error {
     }
 }
 
+/// A packet compressor.
+///
+/// ENet compresses every datagram it sends and decompresses every compressed datagram it receives exactly once,
+/// this crate never invokes the compressor on its own.
 pub trait Compressor {
     /// Compress input buffers into an output buffer.
     ///
@@ -83,7 +88,7 @@ impl OutputBuffer {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.length != 0
+        self.length == 0
     }
 
     /// Number of written bytes.
@@ -100,11 +105,10 @@ impl Write for OutputBuffer {
             return Err(ErrorKind::WriteZero.into());
         }
 
-        for (i, b) in data.iter().copied().enumerate() {
-            unsafe {
-                self.buffer.add(i).write(b);
-            }
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), self.buffer.add(self.written), data.len());
         }
+        self.written += data.len();
 
         Ok(data.len())
     }
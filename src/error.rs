@@ -6,6 +6,14 @@ use std::io;
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// The connection attempt failed.
+    ConnectionFailed,
+    /// A message couldn't be decoded.
+    #[cfg(feature = "serde")]
+    Decode(Box<dyn error::Error + Send + Sync>),
+    /// A message couldn't be encoded.
+    #[cfg(feature = "serde")]
+    Encode(Box<dyn error::Error + Send + Sync>),
     /// Library initialization failed.
     Init,
     /// An invalid argument was passed to a function.
@@ -20,6 +28,8 @@ impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match &self {
             Self::Io(err) => Some(err),
+            #[cfg(feature = "serde")]
+            Self::Decode(err) | Self::Encode(err) => Some(err.as_ref()),
             _ => None,
         }
     }
@@ -34,6 +44,11 @@ impl From<io::Error> for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match &self {
+            Self::ConnectionFailed => write!(f, "Connection failed"),
+            #[cfg(feature = "serde")]
+            Self::Decode(err) => write!(f, "Message decoding failed: {}", err),
+            #[cfg(feature = "serde")]
+            Self::Encode(err) => write!(f, "Message encoding failed: {}", err),
             Self::Init => write!(f, "Library initialization failed"),
             Self::InvalidArgument => write!(f, "Invalid argument"),
             Self::Io(err) => write!(f, "{}", err),
@@ -1,5 +1,6 @@
+use crate::host::Host;
 use crate::packet::Packet;
-use crate::peer::PeerMut;
+use crate::peer::{PeerId, PeerMut};
 
 #[derive(Debug)]
 pub struct Event<'a, T> {
@@ -7,13 +8,106 @@ pub struct Event<'a, T> {
     pub kind: EventKind,
 }
 
+impl<T> Event<'_, T> {
+    /// Returns the identifier of the peer this event is related to.
+    pub fn peer_id(&self) -> PeerId {
+        self.peer.id()
+    }
+
+    /// Converts the event into one that doesn't borrow the host.
+    pub fn into_owned(self) -> OwnedEvent {
+        self.into()
+    }
+}
+
 /// Event variant.
 #[derive(Debug)]
 pub enum EventKind {
     /// A peer connected.
     Connect(u32),
     /// A peer disconnected.
-    Disconnect(u32),
+    Disconnect(DisconnectReason),
     /// A packet was received from a peer.
     Receive(Packet),
+    /// Packets were queued on a peer by a host built with [`HostBuilder::queued_receive`](crate::host::HostBuilder::queued_receive)
+    /// and can be obtained through [`PeerMut::receive`] or [`PeerMut::receive_on`].
+    ///
+    /// Generated once until packets are received from the peer again.
+    DataAvailable,
+}
+
+/// Why a peer disconnected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DisconnectReason {
+    /// The foreign host disconnected, passing the contained data.
+    ///
+    /// This is also how a connection rejected by the [accept filter](crate::host::HostBuilder::accept_filter) or
+    /// [peer data factory](crate::host::HostBuilder::with_peer_data_factory) of the foreign host ends,
+    /// after a connect event, with the rejection code as data.
+    Remote(u32),
+    /// The connection timed out, e.g. because the foreign host crashed or became unreachable.
+    Timeout,
+    /// The connection was never established, because the foreign host is unreachable or has no free slots.
+    ///
+    /// Contains the data passed by the foreign host if it disconnected during the handshake, zero otherwise.
+    /// Hosts built with this crate never do, as they reject connections only after the handshake, see [`DisconnectReason::Remote`].
+    ConnectFailed(u32),
+    /// The disconnection was requested locally through [`PeerMut::disconnect`] or [`PeerMut::disconnect_later`], passing the contained data.
+    Local(u32),
+    /// The peer was disconnected by a host in queued receive mode, because the packets queued on it
+    /// exceeded [`Host::max_waiting_data`](crate::host::Host::max_waiting_data).
+    ///
+    /// The foreign host sees [`DisconnectReason::Remote`] with [`DISCONNECT_QUEUE_FULL`](crate::host::DISCONNECT_QUEUE_FULL) as data.
+    QueueFull,
+}
+
+/// An event that, unlike [`Event`], doesn't borrow the [`Host`](crate::host::Host) it originated from.
+///
+/// It can be stored or sent to another thread, and the peer can be accessed again through [`Host::peer_mut`](crate::host::Host::peer_mut)
+/// unless it has disconnected since.
+#[derive(Debug)]
+pub struct OwnedEvent {
+    pub peer: PeerId,
+    pub kind: EventKind,
+}
+
+impl<T> From<Event<'_, T>> for OwnedEvent {
+    fn from(event: Event<'_, T>) -> Self {
+        Self {
+            peer: event.peer_id(),
+            kind: event.kind,
+        }
+    }
+}
+
+/// Callbacks invoked by [`Host::run`] for each kind of event.
+///
+/// All methods do nothing by default, so only the events of interest need to be handled.
+pub trait EventHandler<T> {
+    /// A peer connected, see [`EventKind::Connect`].
+    fn on_connect(&mut self, peer: PeerMut<'_, T>, data: u32) {
+        let _ = (peer, data);
+    }
+
+    /// A peer disconnected, see [`EventKind::Disconnect`].
+    fn on_disconnect(&mut self, peer: PeerMut<'_, T>, reason: DisconnectReason) {
+        let _ = (peer, reason);
+    }
+
+    /// A packet was received from a peer, see [`EventKind::Receive`].
+    fn on_receive(&mut self, peer: PeerMut<'_, T>, packet: Packet) {
+        let _ = (peer, packet);
+    }
+
+    /// Packets were queued on a peer, see [`EventKind::DataAvailable`].
+    ///
+    /// Only called for hosts built with [`HostBuilder::queued_receive`](crate::host::HostBuilder::queued_receive).
+    fn on_data_available(&mut self, peer: PeerMut<'_, T>) {
+        let _ = peer;
+    }
+
+    /// Called at the fixed interval passed to [`Host::run`].
+    fn on_tick(&mut self, host: &mut Host<T>) {
+        let _ = host;
+    }
 }
@@ -0,0 +1,139 @@
+//! Thread-safe handles for queueing operations on a host.
+
+use crate::error::Error;
+use crate::packet::Packet;
+use crate::peer::PeerId;
+
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A cloneable handle for queueing operations on a [`Host`](crate::host::Host) from any thread.
+///
+/// ENet hosts can only be used by a single thread, so operations are queued and carried out by the thread owning the host
+/// at the beginning of the next call to [`Host::service`](crate::host::Host::service) or [`Host::flush`](crate::host::Host::flush).
+/// Operations targeting peers that have disconnected in the meantime are discarded.
+pub struct HostHandle<T> {
+    commands: Arc<Mutex<Vec<Command<T>>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl<T> HostHandle<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            commands: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Requests [`Host::run`](crate::host::Host::run) to return once the event or tick being handled is done.
+    ///
+    /// If the host isn't running, the next call to [`Host::run`](crate::host::Host::run) returns right away.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Queues a packet to be sent to a peer.
+    pub fn send(&self, peer: PeerId, packet: Packet) {
+        self.push(Command::Send { peer, packet });
+    }
+
+    /// Queues a packet to be broadcast to all peers.
+    pub fn broadcast(&self, packet: Packet) {
+        self.push(Command::Broadcast(packet));
+    }
+
+    /// Queues a disconnection request, see [`PeerMut::disconnect`](crate::peer::PeerMut::disconnect).
+    pub fn disconnect(&self, peer: PeerId, data: u32) {
+        self.push(Command::Disconnect { peer, data });
+    }
+
+    /// Queues a disconnection request to be performed once all queued outgoing packets are sent, see [`PeerMut::disconnect_later`](crate::peer::PeerMut::disconnect_later).
+    pub fn disconnect_later(&self, peer: PeerId, data: u32) {
+        self.push(Command::DisconnectLater { peer, data });
+    }
+
+    /// Queues a connection attempt to a foreign host, see [`Host::connect`](crate::host::Host::connect).
+    ///
+    /// Addresses are resolved and validated immediately, `value` becomes the data of the peer once the connection is initiated.
+    /// Once the connection is initiated, the peer can be obtained from the [`EventKind::Connect`](crate::event::EventKind::Connect) event.
+    /// If it can't be initiated, for instance because all peer slots of the host are in use, the attempt is discarded.
+    pub fn connect(
+        &self,
+        addrs: impl ToSocketAddrs,
+        channel_count: usize,
+        data: u32,
+        value: T,
+    ) -> Result<(), Error> {
+        if channel_count == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let addr = match addrs.to_socket_addrs()?.next() {
+            Some(addr) => addr,
+            None => return Err(Error::InvalidArgument),
+        };
+
+        self.push(Command::Connect {
+            addr,
+            channel_count,
+            data,
+            value,
+        });
+
+        Ok(())
+    }
+
+    /// Takes all queued commands.
+    pub(crate) fn take(&self) -> Vec<Command<T>> {
+        mem::take(&mut *self.commands.lock().unwrap())
+    }
+
+    /// Takes a pending stop request.
+    pub(crate) fn take_stop(&self) -> bool {
+        self.stop.swap(false, Ordering::Relaxed)
+    }
+
+    fn push(&self, command: Command<T>) {
+        self.commands.lock().unwrap().push(command);
+    }
+}
+
+impl<T> Clone for HostHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+            stop: self.stop.clone(),
+        }
+    }
+}
+
+impl<T> Debug for HostHandle<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HostHandle").finish_non_exhaustive()
+    }
+}
+
+pub(crate) enum Command<T> {
+    Send {
+        peer: PeerId,
+        packet: Packet,
+    },
+    Broadcast(Packet),
+    Disconnect {
+        peer: PeerId,
+        data: u32,
+    },
+    DisconnectLater {
+        peer: PeerId,
+        data: u32,
+    },
+    Connect {
+        addr: SocketAddr,
+        channel_count: usize,
+        data: u32,
+        value: T,
+    },
+}
@@ -1,131 +1,477 @@
+use crate::addr;
+use crate::channel::{Channel, Delivery};
+use crate::checksum::{self, Checksum, ChecksumCtx};
 use crate::compress::{Compressor, InputBuffer, OutputBuffer};
 use crate::error::Error;
-use crate::event::{Event, EventKind};
+use crate::event::{DisconnectReason, Event, EventHandler, EventKind, OwnedEvent};
+use crate::handle::{Command, HostHandle};
 use crate::init::InitGuard;
+use crate::intercept::{self, InterceptCtx, Interceptor};
 use crate::packet::Packet;
-use crate::peer::{self, Peer, PeerMut};
+use crate::peer::{self, Peer, PeerId, PeerInfo, PeerMut};
+use crate::protocol;
 
 use core::slice;
-use enet_sys::{ENetAddress, ENetBuffer, ENetCompressor, ENetEvent, ENetHost};
+use enet_sys::{ENetBuffer, ENetCompressor, ENetEvent, ENetHost, ENetPeer};
 use libc::{c_void, size_t};
 use std::any::Any;
 use std::convert::TryInto;
 use std::fmt::{self, Debug, Formatter};
 use std::io;
 use std::marker::PhantomData;
-use std::mem::MaybeUninit;
-use std::net::{SocketAddr, SocketAddrV4, ToSocketAddrs};
+use std::mem::{self, MaybeUninit};
+use std::net::{SocketAddr, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, AsSocket, BorrowedSocket, RawSocket};
 use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub const CHANNEL_COUNT_MAX: usize = enet_sys::ENET_PROTOCOL_MAXIMUM_CHANNEL_COUNT as usize;
 
+pub const MTU_MIN: u32 = enet_sys::ENET_PROTOCOL_MINIMUM_MTU;
+pub const MTU_MAX: u32 = enet_sys::ENET_PROTOCOL_MAXIMUM_MTU;
+
+/// Data sent to a peer disconnected for exceeding [`Host::max_waiting_data`] in queued receive mode.
+///
+/// The foreign host reports it as [`DisconnectReason::Remote`], telling the disconnection apart from a graceful one.
+pub const DISCONNECT_QUEUE_FULL: u32 = u32::MAX;
+
 /// The host structure used for communicating with other peers.
 pub struct Host<T> {
     // Order is important here.
     // The host pointer has to be destroyed before the init guard.
     guard: InitGuard,
     compressor_ctx: Box<CompressorCtx>,
+    checksum_ctx: Option<Box<ChecksumCtx>>,
+    intercept_ctx: Option<Box<InterceptCtx>>,
+    accept_filter: Option<Box<AcceptFilter>>,
+    peer_data_factory: Box<PeerDataFactory<T>>,
+    channels: Vec<(String, Delivery)>,
+    queued_receive: bool,
+    handle: HostHandle<T>,
     host: *mut ENetHost,
     _marker: PhantomData<T>,
 }
 
-impl<T: Default> Host<T> {
-    /// Create a new builder. Convenience function.
+impl<T: Default + 'static> Host<T> {
+    /// Create a new builder with peer data being default-initialized. Convenience function.
     pub fn builder() -> HostBuilder<T> {
         HostBuilder::default()
     }
+}
 
+impl<T> Host<T> {
     /// Broadcasts a packet to all peers associated with this host.
     pub fn broadcast(&mut self, packet: Packet) {
         unsafe {
+            // ENet sends the packet to every connected peer.
+            let host = &*self.host;
+            for i in 0..host.peerCount {
+                let peer = host.peers.add(i);
+                if (*peer).state == enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTED {
+                    peer::count_sent(peer, packet.data().len());
+                }
+            }
+
             enet_sys::enet_host_broadcast(self.host, packet.channel_id(), packet.into_raw());
         }
     }
 
     /// Checks for any queued events on the host and dispatches one if available.
     pub fn check_events(&mut self) -> Result<Option<Event<'_, T>>, Error> {
-        let mut event = MaybeUninit::uninit();
+        let event = loop {
+            let mut event = MaybeUninit::uninit();
 
-        let ret = unsafe { enet_sys::enet_host_check_events(self.host, event.as_mut_ptr()) };
-        if ret < 0 {
-            self.panic_check();
-            return Err(Error::Unknown);
-        }
+            let ret = unsafe { enet_sys::enet_host_check_events(self.host, event.as_mut_ptr()) };
+            if ret < 0 {
+                self.panic_check();
+                return Err(Error::Unknown);
+            }
 
-        if ret == 0 {
-            return Ok(None);
-        }
+            if ret == 0 {
+                return Ok(None);
+            }
+
+            let event = unsafe { event.assume_init() };
+            if unsafe { self.accept_event(&event) } {
+                break event;
+            }
+        };
 
-        unsafe { Ok(self.translate_event(event.assume_init())) }
+        unsafe { Ok(self.translate_event(event)) }
     }
 
-    /// Initiates a connection to a foreign host identified by the first IPv4 socket address resolved from `addrs`.
+    /// Initiates a connection to a foreign host identified by the first socket address resolved from `addrs`.
     ///
-    /// The peer returned will have not completed the connection until [`Host::service`] notifies of an [`EventKind::Connect`] event for the peer.
+    /// The peer returned carries `value` as its data right away,
+    /// but will have not completed the connection until [`Host::service`] notifies of an [`EventKind::Connect`] event for the peer.
+    ///
+    /// `channel_count` has to be non-zero, and at least the number of channels added through [`HostBuilder::channel`].
     pub fn connect(
         &mut self,
         addrs: impl ToSocketAddrs,
         channel_count: usize,
         data: u32,
+        value: T,
     ) -> Result<PeerMut<'_, T>, Error> {
-        if channel_count == 0 {
+        if channel_count == 0 || channel_count < self.channels.len() {
             return Err(Error::InvalidArgument);
         }
 
-        for addr in addrs.to_socket_addrs()? {
-            let addr = match addr {
-                SocketAddr::V4(addr) => addr,
-                SocketAddr::V6(_) => continue,
-            };
-
-            let addr = ENetAddress {
-                host: u32::from_ne_bytes(addr.ip().octets()),
-                port: addr.port(),
-            };
-
-            let peer =
-                unsafe { enet_sys::enet_host_connect(self.host, &addr, channel_count, data) };
-            if peer.is_null() {
-                return Err(Error::Unknown);
-            }
+        let addr = match addrs.to_socket_addrs()?.next() {
+            Some(addr) => addr::to_enet(addr),
+            None => return Err(Error::InvalidArgument),
+        };
 
-            return Ok(unsafe { PeerMut::from_raw(peer, false) });
+        let peer = unsafe { enet_sys::enet_host_connect(self.host, &addr, channel_count, data) };
+        if peer.is_null() {
+            return Err(Error::Unknown);
         }
 
-        Err(Error::InvalidArgument)
+        unsafe {
+            peer::attach_data(peer, value);
+            Ok(PeerMut::from_raw(peer, false))
+        }
     }
 
     /// Sends any queued packets on the host specified to its designated peers.
     // This function need only be used in circumstances where one wishes to send queued packets earlier than in a call to [`Host::service()`].
     pub fn flush(&mut self) {
+        self.process_commands();
+
+        let _scope = self.checksum_scope();
         unsafe {
             enet_sys::enet_host_flush(self.host);
         }
+
+        self.panic_check();
     }
 
     /// Waits for events on the host specified and shuttles packets between the host and its peers.
     pub fn service(&mut self, timeout: Duration) -> Result<Option<Event<'_, T>>, Error> {
-        let mut event = MaybeUninit::uninit();
+        self.process_commands();
 
-        let ret = unsafe {
-            enet_sys::enet_host_service(
-                self.host,
-                event.as_mut_ptr(),
-                timeout.as_millis().try_into().unwrap(),
-            )
-        };
+        // Events filtered out by accept_event shouldn't cut the wait short.
+        let deadline = Instant::now() + timeout;
+        let event = loop {
+            let mut event = MaybeUninit::uninit();
+
+            // The scopes end before accept_event runs user callbacks, which may call into other hosts.
+            let ret = {
+                let _scope = self.checksum_scope();
+                let _intercept_scope = self
+                    .intercept_ctx
+                    .as_deref_mut()
+                    .map(|ctx| intercept::enter(ctx));
+
+                unsafe {
+                    enet_sys::enet_host_service(
+                        self.host,
+                        event.as_mut_ptr(),
+                        deadline
+                            .saturating_duration_since(Instant::now())
+                            .as_millis()
+                            .try_into()
+                            .unwrap(),
+                    )
+                }
+            };
 
-        if ret < 0 {
             self.panic_check();
-            return Err(Error::Unknown);
+            if ret < 0 {
+                return Err(Error::Unknown);
+            }
+
+            let event = unsafe { event.assume_init() };
+            if unsafe { self.accept_event(&event) } {
+                break event;
+            }
+        };
+
+        Ok(unsafe { self.translate_event(event) })
+    }
+
+    /// Waits for events like [`Host::service`], then services the host without waiting until no events are left,
+    /// appending all of them to `events`.
+    ///
+    /// Since the events don't borrow the host, they can be processed after this call returns while the host is used freely.
+    /// Returns the number of events appended.
+    pub fn service_batch(
+        &mut self,
+        timeout: Duration,
+        events: &mut Vec<OwnedEvent>,
+    ) -> Result<usize, Error> {
+        let len = events.len();
+
+        match self.service(timeout)? {
+            Some(event) => events.push(event.into()),
+            None => return Ok(0),
+        }
+
+        // ENet stops receiving at a connect or disconnect event and reads a limited number of datagrams per call,
+        // so the socket may still hold more.
+        while let Some(event) = self.service(Duration::ZERO)? {
+            events.push(event.into());
         }
 
-        Ok(unsafe { self.translate_event(event.assume_init()) })
+        Ok(events.len() - len)
     }
 
-    /// Creates an iterator over all currently connected peers.
+    /// Services the host and dispatches events to `handler` until stopped through [`HostHandle::stop`].
+    ///
+    /// [`EventHandler::on_tick`] is called every `tick_interval`, which has to be non-zero. Ticks are skipped rather than
+    /// called in quick succession if handling events takes longer than that.
+    pub fn run(
+        &mut self,
+        handler: &mut impl EventHandler<T>,
+        tick_interval: Duration,
+    ) -> Result<(), Error> {
+        if tick_interval.is_zero() {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut next_tick = Instant::now() + tick_interval;
+
+        loop {
+            if self.handle.take_stop() {
+                return Ok(());
+            }
+
+            let now = Instant::now();
+            if now >= next_tick {
+                handler.on_tick(self);
+
+                next_tick += tick_interval;
+                if next_tick <= now {
+                    next_tick = now + tick_interval;
+                }
+
+                continue;
+            }
+
+            let Event { peer, kind } = match self.service(next_tick - now)? {
+                Some(event) => event,
+                None => continue,
+            };
+
+            match kind {
+                EventKind::Connect(data) => handler.on_connect(peer, data),
+                EventKind::Disconnect(reason) => handler.on_disconnect(peer, reason),
+                EventKind::Receive(packet) => handler.on_receive(peer, packet),
+                EventKind::DataAvailable => handler.on_data_available(peer),
+            }
+        }
+    }
+
+    /// Shuttles packets between the host and its peers without waiting, processing only data that's already available.
+    ///
+    /// Together with the socket exposed through [`AsRawFd`](std::os::unix::io::AsRawFd), this allows driving the host from an external event loop.
+    pub fn service_nonblocking(&mut self) -> Result<Option<Event<'_, T>>, Error> {
+        self.service(Duration::ZERO)
+    }
+
+    /// Looks up a channel configured through [`HostBuilder::channel`] by its name.
+    pub fn channel(&self, name: &str) -> Option<Channel> {
+        self.channels
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|id| Channel::new(id as u8, self.channels[id].1))
+    }
+
+    /// Returns the channels configured through [`HostBuilder::channel`] along with their names, in order of their IDs.
+    pub fn channels(&self) -> impl Iterator<Item = (&str, Channel)> + '_ {
+        self.channels
+            .iter()
+            .enumerate()
+            .map(|(id, (name, delivery))| (name.as_str(), Channel::new(id as u8, *delivery)))
+    }
+
+    /// Returns the maximum number of channels allowed for incoming connections.
+    pub fn channel_limit(&self) -> usize {
+        unsafe { (*self.host).channelLimit }
+    }
+
+    /// Limits the maximum number of channels allowed for future incoming connections.
+    ///
+    /// The value has to be non-zero, and at least the number of channels added through [`HostBuilder::channel`].
+    /// Values above [`CHANNEL_COUNT_MAX`] are clamped.
+    pub fn set_channel_limit(&mut self, value: usize) -> Result<(), Error> {
+        if value == 0 || value < self.channels.len() {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            enet_sys::enet_host_channel_limit(self.host, value);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the incoming bandwidth limit in bytes/second, or `None` if unlimited.
+    pub fn incoming_bandwidth(&self) -> Option<u32> {
+        bandwidth(unsafe { (*self.host).incomingBandwidth })
+    }
+
+    /// Returns the outgoing bandwidth limit in bytes/second, or `None` if unlimited.
+    pub fn outgoing_bandwidth(&self) -> Option<u32> {
+        bandwidth(unsafe { (*self.host).outgoingBandwidth })
+    }
+
+    /// Adjusts the bandwidth limits in bytes/second, `None` meaning unlimited.
+    ///
+    /// Connected peers are notified of the new limits. The values have to be non-zero.
+    pub fn set_bandwidth_limit(
+        &mut self,
+        incoming: Option<u32>,
+        outgoing: Option<u32>,
+    ) -> Result<(), Error> {
+        if incoming == Some(0) || outgoing == Some(0) {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            enet_sys::enet_host_bandwidth_limit(
+                self.host,
+                incoming.unwrap_or(0),
+                outgoing.unwrap_or(0),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns the maximum number of peers allowed to be connected from the same IP address.
+    pub fn duplicate_peers(&self) -> usize {
+        unsafe { (*self.host).duplicatePeers }
+    }
+
+    /// Sets the maximum number of peers allowed to be connected from the same IP address.
+    ///
+    /// Further connection attempts from the address are ignored. Established connections are kept.
+    /// The value has to be non-zero.
+    pub fn set_duplicate_peers(&mut self, value: usize) -> Result<(), Error> {
+        if value == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            (*self.host).duplicatePeers = value;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the maximum transmission unit used for new connections.
+    pub fn mtu(&self) -> u32 {
+        unsafe { (*self.host).mtu }
+    }
+
+    /// Sets the maximum transmission unit used for new connections.
+    ///
+    /// The value has to be between [`MTU_MIN`] and [`MTU_MAX`]. Established connections keep their MTU.
+    pub fn set_mtu(&mut self, value: u32) -> Result<(), Error> {
+        if !(MTU_MIN..=MTU_MAX).contains(&value) {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            (*self.host).mtu = value;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the maximum size of packets that can be sent or received.
+    pub fn max_packet_size(&self) -> usize {
+        unsafe { (*self.host).maximumPacketSize }
+    }
+
+    /// Sets the maximum size of packets that can be sent or received.
+    ///
+    /// Sending a larger packet fails and larger incoming packets are dropped by ENet. The value has to be non-zero.
+    pub fn set_max_packet_size(&mut self, value: usize) -> Result<(), Error> {
+        if value == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            (*self.host).maximumPacketSize = value;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the maximum amount of received data buffered per peer.
+    ///
+    /// In queued receive mode, it limits the packets queued on each peer instead, see [`HostBuilder::max_waiting_data`].
+    pub fn max_waiting_data(&self) -> usize {
+        unsafe { (*self.host).maximumWaitingData }
+    }
+
+    /// Sets the maximum amount of received data buffered per peer.
+    ///
+    /// Once a peer exceeds it, ENet drops further incoming packets from the peer until the buffered ones are dispatched.
+    /// The value has to be non-zero.
+    pub fn set_max_waiting_data(&mut self, value: usize) -> Result<(), Error> {
+        if value == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        unsafe {
+            (*self.host).maximumWaitingData = value;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of received datagrams dropped because their checksum didn't match.
+    ///
+    /// Always zero if no checksum is configured through [`HostBuilder::checksum`].
+    pub fn checksum_mismatches(&self) -> u64 {
+        self.checksum_ctx
+            .as_ref()
+            .map(|ctx| ctx.mismatches)
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of received datagrams discarded through [`Action::Ignore`](crate::intercept::Action::Ignore).
+    ///
+    /// Always zero if no interceptor is configured through [`HostBuilder::intercept`].
+    pub fn ignored_datagrams(&self) -> u64 {
+        self.intercept_ctx
+            .as_ref()
+            .map(|ctx| ctx.ignored)
+            .unwrap_or(0)
+    }
+
+    /// Returns a handle for queueing operations on this host from other threads.
+    pub fn handle(&self) -> HostHandle<T> {
+        self.handle.clone()
+    }
+
+    /// Returns the peer identified by `id`.
+    ///
+    /// Returns `None` if the peer has disconnected since the identifier was obtained.
+    pub fn peer(&self, id: PeerId) -> Option<Peer<'_, T>> {
+        self.find_peer(id)
+            .map(|peer| unsafe { Peer::from_raw(peer) })
+    }
+
+    /// Returns the peer identified by `id`.
+    ///
+    /// Returns `None` if the peer has disconnected since the identifier was obtained.
+    pub fn peer_mut(&mut self, id: PeerId) -> Option<PeerMut<'_, T>> {
+        self.find_peer(id)
+            .map(|peer| unsafe { PeerMut::from_raw(peer, false) })
+    }
+
+    /// Creates an iterator over all peers the host knows about.
+    ///
+    /// Besides connected peers, this includes outgoing connections initiated through [`Host::connect`] that aren't
+    /// established yet and peers whose disconnection hasn't been reported yet. [`PeerInfo::state`] tells them apart.
     pub fn peers(&self) -> Peers<'_, T> {
         Peers {
             host: self,
@@ -133,7 +479,10 @@ impl<T: Default> Host<T> {
         }
     }
 
-    /// Creates an iterator over all currently connected peers.
+    /// Creates an iterator over all peers the host knows about.
+    ///
+    /// Besides connected peers, this includes outgoing connections initiated through [`Host::connect`] that aren't
+    /// established yet and peers whose disconnection hasn't been reported yet. [`PeerInfo::state`] tells them apart.
     pub fn peers_mut(&mut self) -> PeersMut<'_, T> {
         PeersMut {
             host: self,
@@ -141,10 +490,99 @@ impl<T: Default> Host<T> {
         }
     }
 
+    /// Resets the traffic statistics returned by [`Host::stats`] to zero.
+    ///
+    /// Statistics of peers are reset separately through [`PeerMut::reset_stats`].
+    pub fn reset_stats(&mut self) {
+        unsafe {
+            let host = &mut *self.host;
+            host.totalSentData = 0;
+            host.totalSentPackets = 0;
+            host.totalReceivedData = 0;
+            host.totalReceivedPackets = 0;
+        }
+    }
+
+    /// Returns a snapshot of the traffic statistics of this host.
+    ///
+    /// The counters are accumulated since the host was created or [`Host::reset_stats`] was last called.
+    pub fn stats(&self) -> Stats {
+        let host = unsafe { &*self.host };
+        Stats {
+            sent_bytes: host.totalSentData,
+            sent_datagrams: host.totalSentPackets,
+            received_bytes: host.totalReceivedData,
+            received_datagrams: host.totalReceivedPackets,
+        }
+    }
+
+    /// Returns the peer identified by `id`, if it's still around.
+    fn find_peer(&self, id: PeerId) -> Option<*mut ENetPeer> {
+        let host = unsafe { &*self.host };
+        if id.index >= host.peerCount {
+            return None;
+        }
+
+        let peer = unsafe { host.peers.add(id.index) };
+        if unsafe { (*peer).data.is_null() } || unsafe { PeerId::from_raw::<T>(peer) } != id {
+            return None;
+        }
+
+        Some(peer)
+    }
+
+    /// Carries out operations queued through handles.
+    fn process_commands(&mut self) {
+        for command in self.handle.take() {
+            match command {
+                Command::Send { peer, packet } => {
+                    if let Some(peer) = self.find_peer(peer) {
+                        // Failures are treated like sending to a peer that has disconnected already.
+                        let _ = unsafe { PeerMut::<T>::from_raw(peer, false) }.send(packet);
+                    }
+                }
+                Command::Broadcast(packet) => self.broadcast(packet),
+                Command::Disconnect { peer, data } => {
+                    if let Some(peer) = self.find_peer(peer) {
+                        unsafe { PeerMut::<T>::from_raw(peer, false) }.disconnect(data);
+                    }
+                }
+                Command::DisconnectLater { peer, data } => {
+                    if let Some(peer) = self.find_peer(peer) {
+                        unsafe { PeerMut::<T>::from_raw(peer, false) }.disconnect_later(data);
+                    }
+                }
+                Command::Connect {
+                    addr,
+                    channel_count,
+                    data,
+                    value,
+                } => {
+                    let _ = self.connect(addr, channel_count, data, value);
+                }
+            }
+        }
+    }
+
     fn panic_check(&mut self) {
         if let Some(panic) = self.compressor_ctx.panic.take() {
             panic::resume_unwind(panic);
         }
+
+        if let Some(panic) = self.checksum_ctx.as_mut().and_then(|ctx| ctx.panic.take()) {
+            panic::resume_unwind(panic);
+        }
+
+        if let Some(panic) = self.intercept_ctx.as_mut().and_then(|ctx| ctx.panic.take()) {
+            panic::resume_unwind(panic);
+        }
+    }
+
+    /// Makes the checksum available to ENet, which has to be done around every call that may send or receive data.
+    fn checksum_scope(&mut self) -> Option<checksum::Scope> {
+        self.checksum_ctx
+            .as_deref_mut()
+            .map(|ctx| checksum::enter(ctx))
     }
 
     fn set_compressor(&mut self, kind: Option<CompressorKind>) -> Result<(), Error> {
@@ -164,10 +602,23 @@ impl<T: Default> Host<T> {
                 }
             }
             Some(CompressorKind::RangeCoder) => {
-                if unsafe { enet_sys::enet_host_compress_with_range_coder(self.host) } < 0 {
+                let context = unsafe { enet_sys::enet_range_coder_create() };
+                if context.is_null() {
                     return Err(Error::Unknown);
                 }
 
+                // Installed like `enet_host_compress_with_range_coder` does, but decompressed datagrams are inspected as well.
+                let enet_compressor = ENetCompressor {
+                    compress: Some(enet_sys::enet_range_coder_compress),
+                    context,
+                    decompress: Some(range_coder_decompress),
+                    destroy: Some(enet_sys::enet_range_coder_destroy),
+                };
+
+                unsafe {
+                    enet_sys::enet_host_compress(self.host, &enet_compressor as *const _);
+                }
+
                 self.compressor_ctx.compressor = None;
             }
             None => {
@@ -182,24 +633,103 @@ impl<T: Default> Host<T> {
         Ok(())
     }
 
+    /// Prepares peer data for an event, returning whether the event should be reported at all.
+    unsafe fn accept_event(&mut self, event: &ENetEvent) -> bool {
+        match event.type_ {
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_CONNECT => {
+                if (*event.peer).data.is_null() {
+                    let addr = addr::from_enet(&(*event.peer).address);
+
+                    let value = match &mut self.accept_filter {
+                        Some(accept_filter) => accept_filter(addr, event.data),
+                        None => Ok(()),
+                    }
+                    .and_then(|_| {
+                        (self.peer_data_factory)(event.data, PeerInfo::from_raw(event.peer))
+                    });
+
+                    match value {
+                        Ok(value) => peer::attach_data(event.peer, value),
+                        Err(code) => {
+                            // The disconnect event generated for the peer later on is skipped because no data is attached.
+                            let _scope = self.checksum_scope();
+                            enet_sys::enet_peer_disconnect(event.peer, code);
+
+                            return false;
+                        }
+                    }
+                }
+
+                peer::data_mut::<T>(event.peer).connected = true;
+
+                true
+            }
+            // ENet may report the disconnection of a peer whose connection was never reported.
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_DISCONNECT => !(*event.peer).data.is_null(),
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_RECEIVE => {
+                peer::count_received(event.peer, (*event.packet).dataLength);
+
+                if !self.queued_receive || (*event.peer).data.is_null() {
+                    return true;
+                }
+
+                let data = peer::data_mut::<T>(event.peer);
+                let packet = Packet::from_raw(event.packet, event.channelID, self.guard.clone());
+
+                // ENet hands packets over right away in this mode, so its limit on waiting data is enforced here instead.
+                // Packets can't be refused like ENet does without breaking reliable delivery, so the peer is disconnected.
+                if data.queued_bytes >= (*self.host).maximumWaitingData {
+                    if !mem::replace(&mut data.queue_full, true) {
+                        let _scope = self.checksum_scope();
+                        enet_sys::enet_peer_disconnect(event.peer, DISCONNECT_QUEUE_FULL);
+                    }
+
+                    return false;
+                }
+
+                data.enqueue(packet);
+
+                // Only the first packet since the peer was last received from generates an event.
+                !mem::replace(&mut data.notified, true)
+            }
+            _ => true,
+        }
+    }
+
+    /// The event has to be accepted by [`Host::accept_event`] first.
     unsafe fn translate_event(&self, event: ENetEvent) -> Option<Event<'_, T>> {
         let (kind, peer) = match event.type_ {
             enet_sys::_ENetEventType_ENET_EVENT_TYPE_NONE => return None,
-            enet_sys::_ENetEventType_ENET_EVENT_TYPE_CONNECT => {
-                let peer = &mut *event.peer;
-                if peer.data.is_null() {
-                    peer.data = Box::leak(Box::new(T::default())) as *mut _ as *mut _;
-                }
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_CONNECT => (
+                EventKind::Connect(event.data),
+                PeerMut::from_raw(event.peer, false),
+            ),
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_DISCONNECT => {
+                // ENet has reset the peer by now, so the reason is derived from what was tracked beforehand.
+                let data = peer::data::<T>(event.peer);
+                let reason = match (data.disconnect_data, data.connected) {
+                    (Some(data), _) => DisconnectReason::Local(data),
+                    (None, _) if data.queue_full => DisconnectReason::QueueFull,
+                    (None, false) => DisconnectReason::ConnectFailed(event.data),
+                    (None, true) if data.header.remote_disconnect || event.data != 0 => {
+                        DisconnectReason::Remote(event.data)
+                    }
+                    (None, true) => DisconnectReason::Timeout,
+                };
 
                 (
-                    EventKind::Connect(event.data),
+                    EventKind::Disconnect(reason),
+                    PeerMut::from_raw(event.peer, true),
+                )
+            }
+            enet_sys::_ENetEventType_ENET_EVENT_TYPE_RECEIVE
+                if self.queued_receive && !(*event.peer).data.is_null() =>
+            {
+                (
+                    EventKind::DataAvailable,
                     PeerMut::from_raw(event.peer, false),
                 )
             }
-            enet_sys::_ENetEventType_ENET_EVENT_TYPE_DISCONNECT => (
-                EventKind::Disconnect(event.data),
-                PeerMut::from_raw(event.peer, true),
-            ),
             enet_sys::_ENetEventType_ENET_EVENT_TYPE_RECEIVE => (
                 EventKind::Receive(Packet::from_raw(
                     event.packet,
@@ -215,12 +745,42 @@ impl<T: Default> Host<T> {
     }
 }
 
-impl<T: Debug + Default> Debug for Host<T> {
+impl<T: Debug> Debug for Host<T> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("Host").finish_non_exhaustive()
     }
 }
 
+#[cfg(unix)]
+impl<T> AsRawFd for Host<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        unsafe { (*self.host).socket }
+    }
+}
+
+#[cfg(unix)]
+impl<T> AsFd for Host<T> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // The socket is open for as long as the host exists.
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
+#[cfg(windows)]
+impl<T> AsRawSocket for Host<T> {
+    fn as_raw_socket(&self) -> RawSocket {
+        unsafe { (*self.host).socket as RawSocket }
+    }
+}
+
+#[cfg(windows)]
+impl<T> AsSocket for Host<T> {
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        // The socket is open for as long as the host exists.
+        unsafe { BorrowedSocket::borrow_raw(self.as_raw_socket()) }
+    }
+}
+
 impl<T> Drop for Host<T> {
     fn drop(&mut self) {
         unsafe {
@@ -230,24 +790,108 @@ impl<T> Drop for Host<T> {
                 peer::drop_data::<T>(host.peers.add(i));
             }
 
+            if self.checksum_ctx.is_some() {
+                checksum::unregister(self.host);
+            }
+
             enet_sys::enet_host_destroy(self.host);
         }
     }
 }
 
-#[derive(Default)]
+type AcceptFilter = dyn FnMut(SocketAddr, u32) -> Result<(), u32>;
+type PeerDataFactory<T> = dyn FnMut(u32, PeerInfo<'_>) -> Result<T, u32>;
+
+/// ENet represents unlimited bandwidth as zero.
+fn bandwidth(value: u32) -> Option<u32> {
+    if value == 0 {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Traffic statistics of a host, as returned by [`Host::stats`].
+///
+/// Counters are maintained by ENet as 32-bit values and wrap around on overflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Total number of bytes sent, including protocol overhead.
+    pub sent_bytes: u32,
+    /// Total number of UDP datagrams sent.
+    pub sent_datagrams: u32,
+    /// Total number of bytes received, including protocol overhead.
+    pub received_bytes: u32,
+    /// Total number of UDP datagrams received.
+    pub received_datagrams: u32,
+}
+
 pub struct HostBuilder<T> {
-    addr: Option<Result<SocketAddrV4, io::Error>>,
+    addr: Option<Result<SocketAddr, io::Error>>,
     peer_count: Option<usize>,
+    duplicate_peers: Option<usize>,
     channel_limit: Option<usize>,
     incoming_bandwidth: Option<u32>,
     outgoing_bandwidth: Option<u32>,
     compressor_kind: Option<CompressorKind>,
-    _data: PhantomData<T>,
+    accept_filter: Option<Box<AcceptFilter>>,
+    mtu: Option<u32>,
+    max_packet_size: Option<usize>,
+    max_waiting_data: Option<usize>,
+    checksum_kind: Option<ChecksumKind>,
+    interceptor: Option<Box<dyn Interceptor>>,
+    peer_data_factory: Box<PeerDataFactory<T>>,
+    channels: Vec<(String, Delivery)>,
+    queued_receive: bool,
+}
+
+impl<T: Default + 'static> Default for HostBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<T: Default> HostBuilder<T> {
-    /// The address to listen on. The first resolved IPv4 address will be used.
+impl<T: Default + 'static> HostBuilder<T> {
+    /// Create a new builder with peer data being default-initialized.
+    pub fn new() -> Self {
+        Self::with_peer_data_factory(|_, _| Ok(T::default()))
+    }
+}
+
+impl<T> HostBuilder<T> {
+    /// Create a new builder with a factory creating the data associated with an incoming connection,
+    /// based on the data passed to [`Host::connect`] by the foreign host and the peer itself.
+    ///
+    /// The factory is called for connections that passed the [`HostBuilder::accept_filter`].
+    /// Returning an error rejects the connection the same way as the accept filter does.
+    /// Outgoing connections get their data passed to [`Host::connect`] instead.
+    pub fn with_peer_data_factory(
+        factory: impl FnMut(u32, PeerInfo<'_>) -> Result<T, u32> + 'static,
+    ) -> Self {
+        Self {
+            addr: None,
+            peer_count: None,
+            duplicate_peers: None,
+            channel_limit: None,
+            incoming_bandwidth: None,
+            outgoing_bandwidth: None,
+            compressor_kind: None,
+            accept_filter: None,
+            mtu: None,
+            max_packet_size: None,
+            max_waiting_data: None,
+            checksum_kind: None,
+            interceptor: None,
+            peer_data_factory: Box::new(factory),
+            channels: Vec::new(),
+            queued_receive: false,
+        }
+    }
+
+    /// The address to listen on. The first resolved address will be used.
+    ///
+    /// Hosts use dual-stack sockets where the system supports IPv6, so a host bound to an unspecified IPv6
+    /// address like `[::]:1234` also accepts connections over IPv4. Binding to an IPv4 address restricts it to IPv4.
     ///
     /// By default, no address is set and thus the host can't be used as a server.
     pub fn addr(mut self, value: impl ToSocketAddrs) -> Self {
@@ -259,12 +903,13 @@ impl<T: Default> HostBuilder<T> {
             }
         };
 
-        for addr in addrs {
-            if let SocketAddr::V4(addr) = addr {
-                self.addr = Some(Ok(addr));
-                break;
-            }
-        }
+        self.addr = match addrs.into_iter().next() {
+            Some(addr) => Some(Ok(addr)),
+            None => Some(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve to any addresses",
+            ))),
+        };
 
         self
     }
@@ -277,14 +922,46 @@ impl<T: Default> HostBuilder<T> {
         self
     }
 
+    /// The maximum number of peers allowed to be connected from the same IP address. Default is unlimited.
+    ///
+    /// Further connection attempts from the address are ignored. The value has to be non-zero.
+    pub fn duplicate_peers(mut self, value: usize) -> Self {
+        self.duplicate_peers = Some(value);
+        self
+    }
+
     /// The maximum number of channels to allocate for the host. Default is [`CHANNEL_COUNT_MAX`].
     ///
-    /// The value has to be non-zero.
+    /// The value has to be non-zero. If channels are added through [`HostBuilder::channel`], the limit is derived from them
+    /// and doesn't need to be set.
     pub fn channel_limit(mut self, value: usize) -> Self {
         self.channel_limit = Some(value);
         self
     }
 
+    /// Adds a named channel with the given delivery policy. Channels get consecutive IDs in the order they are added.
+    ///
+    /// The channel can later be looked up through [`Host::channel`] and used with [`PeerMut::send_on`].
+    /// Names have to be unique and at most [`CHANNEL_COUNT_MAX`] channels can be added.
+    pub fn channel(mut self, name: impl Into<String>, delivery: Delivery) -> Self {
+        self.channels.push((name.into(), delivery));
+        self
+    }
+
+    /// Whether to leave received packets queued on their peers instead of generating an [`EventKind::Receive`] event for each of them.
+    /// Default is `false`.
+    ///
+    /// An [`EventKind::DataAvailable`] event is generated instead, allowing the packets to be pulled through
+    /// [`PeerMut::receive`] or [`PeerMut::receive_on`] in any order of peers and channels.
+    ///
+    /// Packets queued on a peer are limited by [`HostBuilder::max_waiting_data`]. Since dropping packets would break
+    /// reliable delivery, a peer exceeding the limit is disconnected instead: the host reports [`DisconnectReason::QueueFull`],
+    /// while the foreign host sees [`DISCONNECT_QUEUE_FULL`] as data.
+    pub fn queued_receive(mut self, value: bool) -> Self {
+        self.queued_receive = value;
+        self
+    }
+
     /// Incoming bandwidth limit. Default is unlimited.
     ///
     /// The value has to be non-zero.
@@ -301,47 +978,117 @@ impl<T: Default> HostBuilder<T> {
         self
     }
 
+    /// The maximum transmission unit used for connections. Default is 1392.
+    ///
+    /// The value has to be between [`MTU_MIN`] and [`MTU_MAX`].
+    pub fn mtu(mut self, value: u32) -> Self {
+        self.mtu = Some(value);
+        self
+    }
+
+    /// The maximum size of packets that can be sent or received. Default is 32 MiB.
+    ///
+    /// Sending a larger packet fails and larger incoming packets are dropped by ENet. The value has to be non-zero.
+    pub fn max_packet_size(mut self, value: usize) -> Self {
+        self.max_packet_size = Some(value);
+        self
+    }
+
+    /// The maximum amount of received data buffered per peer. Default is 32 MiB.
+    ///
+    /// Once a peer exceeds it, ENet drops further incoming packets from the peer until the buffered ones are dispatched.
+    /// In [queued receive](HostBuilder::queued_receive) mode, the limit applies to the packets queued on the peer,
+    /// and a peer exceeding it is disconnected with [`DisconnectReason::QueueFull`] as reason and [`DISCONNECT_QUEUE_FULL`] as data.
+    /// The value has to be non-zero.
+    pub fn max_waiting_data(mut self, value: usize) -> Self {
+        self.max_waiting_data = Some(value);
+        self
+    }
+
+    /// Filter deciding whether to accept incoming connections, based on the remote address and the data passed to [`Host::connect`].
+    ///
+    /// Returning an error rejects the connection before any peer data is created and no connect event is generated.
+    /// Since ENet completes the handshake before the data is available, the foreign host sees a connect event first,
+    /// followed by a disconnect event with [`DisconnectReason::Remote`] containing the rejection code.
+    /// A code of zero can't be told apart from a graceful disconnect passing zero as data, so reject with a non-zero code
+    /// if the foreign host needs to know it was rejected.
+    ///
+    /// By default, all connections are accepted.
+    pub fn accept_filter(
+        mut self,
+        value: impl FnMut(SocketAddr, u32) -> Result<(), u32> + 'static,
+    ) -> Self {
+        self.accept_filter = Some(Box::new(value));
+        self
+    }
+
     /// Packet compressor. Default is uncompressed.
     pub fn compressor(mut self, value: CompressorKind) -> Self {
         self.compressor_kind = Some(value);
         self
     }
 
+    /// Checksum for detecting corrupted datagrams, which are dropped when received. Default is none.
+    ///
+    /// Both ends of a connection have to use the same checksum.
+    /// The number of dropped datagrams is available through [`Host::checksum_mismatches`].
+    pub fn checksum(mut self, value: ChecksumKind) -> Self {
+        self.checksum_kind = Some(value);
+        self
+    }
+
+    /// Interceptor inspecting every received datagram before ENet processes it. Default is none.
+    ///
+    /// Any type implementing [`Interceptor`], including closures, can be used.
+    pub fn intercept(mut self, value: impl Interceptor + 'static) -> Self {
+        self.interceptor = Some(Box::new(value));
+        self
+    }
+
     /// Try to create a host based on the configuration.
     pub fn build(self) -> Result<Host<T>, Error> {
         let addr = match self.addr {
-            Some(Ok(addr)) => Some(addr),
+            Some(Ok(addr)) => Some(addr::to_enet(addr)),
             Some(Err(err)) => return Err(err.into()),
             None => None,
         };
 
-        let addr = addr.map(|addr| ENetAddress {
-            host: u32::from_ne_bytes(addr.ip().octets()),
-            port: addr.port(),
-        });
-
         let peer_count = match self.peer_count {
             Some(0) => return Err(Error::InvalidArgument),
             Some(peer_count) => peer_count,
             None => 1,
         };
 
-        let channel_limit = match self.channel_limit {
-            Some(0) => return Err(Error::InvalidArgument),
-            Some(channel_limit) => channel_limit,
-            None => 1,
+        let channels = self.channels;
+        if channels.len() > CHANNEL_COUNT_MAX
+            || channels
+                .iter()
+                .enumerate()
+                .any(|(i, (name, _))| channels[..i].iter().any(|(n, _)| n == name))
+        {
+            return Err(Error::InvalidArgument);
+        }
+
+        // ENet treats zero as the maximum channel count and unlimited bandwidth.
+        let channel_limit = match (self.channel_limit, channels.len()) {
+            (Some(0), _) => return Err(Error::InvalidArgument),
+            (Some(channel_limit), 0) => channel_limit,
+            (Some(channel_limit), len) if channel_limit != len => {
+                return Err(Error::InvalidArgument)
+            }
+            (_, len) => len,
         };
 
         let incoming_bandwidth = match self.incoming_bandwidth {
             Some(0) => return Err(Error::InvalidArgument),
             Some(incoming_bandwidth) => incoming_bandwidth,
-            None => 1,
+            None => 0,
         };
 
         let outgoing_bandwidth = match self.outgoing_bandwidth {
             Some(0) => return Err(Error::InvalidArgument),
             Some(outgoing_bandwidth) => outgoing_bandwidth,
-            None => 1,
+            None => 0,
         };
 
         let guard = InitGuard::new()?;
@@ -367,17 +1114,67 @@ impl<T: Default> HostBuilder<T> {
                 compressor: None,
                 panic: None,
             }),
+            checksum_ctx: None,
+            intercept_ctx: None,
+            accept_filter: self.accept_filter,
+            peer_data_factory: self.peer_data_factory,
+            channels,
+            queued_receive: self.queued_receive,
+            handle: HostHandle::new(),
             host,
             _marker: PhantomData,
         };
 
         host.set_compressor(self.compressor_kind)?;
 
+        if let Some(duplicate_peers) = self.duplicate_peers {
+            host.set_duplicate_peers(duplicate_peers)?;
+        }
+
+        if let Some(mtu) = self.mtu {
+            host.set_mtu(mtu)?;
+        }
+
+        if let Some(max_packet_size) = self.max_packet_size {
+            host.set_max_packet_size(max_packet_size)?;
+        }
+
+        if let Some(max_waiting_data) = self.max_waiting_data {
+            host.set_max_waiting_data(max_waiting_data)?;
+        }
+
+        if let Some(kind) = self.checksum_kind {
+            let checksum: Box<dyn Checksum> = match kind {
+                ChecksumKind::Custom(checksum) => checksum,
+                ChecksumKind::Crc32 => Box::new(checksum::Crc32),
+            };
+
+            let mut ctx = Box::new(ChecksumCtx::new(checksum));
+            unsafe {
+                checksum::register(host.host, ctx.as_mut());
+            }
+
+            host.checksum_ctx = Some(ctx);
+        }
+
+        if let Some(interceptor) = self.interceptor {
+            host.intercept_ctx = Some(Box::new(InterceptCtx {
+                interceptor,
+                panic: None,
+                ignored: 0,
+            }));
+        }
+
+        // Besides running the interceptor, the callback is needed to tell remote disconnects from timeouts.
+        unsafe {
+            (*host.host).intercept = Some(intercept::intercept);
+        }
+
         Ok(host)
     }
 }
 
-/// An iterator over all currently connected peers.
+/// An iterator over all peers the host knows about, see [`Host::peers`].
 pub struct Peers<'a, T> {
     host: &'a Host<T>,
     index: usize,
@@ -405,7 +1202,7 @@ impl<'a, T> Iterator for Peers<'a, T> {
     }
 }
 
-/// An iterator over all currently connected peers.
+/// An iterator over all peers the host knows about, see [`Host::peers`].
 pub struct PeersMut<'a, T> {
     host: &'a mut Host<T>,
     index: usize,
@@ -446,6 +1243,14 @@ pub enum CompressorKind {
     RangeCoder,
 }
 
+/// Checksum for a host.
+pub enum ChecksumKind {
+    /// A custom checksum.
+    Custom(Box<dyn Checksum>),
+    /// The ENet builtin CRC32.
+    Crc32,
+}
+
 unsafe extern "C" fn compress(
     context: *mut c_void,
     input_buffers: *const ENetBuffer,
@@ -469,7 +1274,7 @@ unsafe extern "C" fn compress(
         ctx.compressor
             .as_mut()
             .unwrap()
-            .decompress(input_buffers, &mut output_buffer)
+            .compress(input_buffers, &mut output_buffer)
     }));
 
     match result {
@@ -508,11 +1313,17 @@ unsafe extern "C" fn decompress(
         ctx.compressor
             .as_deref_mut()
             .unwrap()
-            .compress(&[input_buffer], &mut output_buffer)
+            .decompress(&[input_buffer], &mut output_buffer)
     }));
 
     match result {
-        Ok(Ok(_)) => output_buffer.written(),
+        Ok(Ok(_)) => {
+            protocol::note_decompressed(slice::from_raw_parts(
+                output_buffer.buffer,
+                output_buffer.written(),
+            ));
+            output_buffer.written()
+        }
         Ok(Err(_)) => 0,
         Err(err) => {
             ctx.panic = Some(err);
@@ -522,3 +1333,25 @@ unsafe extern "C" fn decompress(
 }
 
 unsafe extern "C" fn destroy(_context: *mut c_void) {}
+
+unsafe extern "C" fn range_coder_decompress(
+    context: *mut c_void,
+    input_buffer: *const u8,
+    input_buffer_length: size_t,
+    output_buffer: *mut u8,
+    output_buffer_length: size_t,
+) -> size_t {
+    let written = enet_sys::enet_range_coder_decompress(
+        context,
+        input_buffer,
+        input_buffer_length,
+        output_buffer,
+        output_buffer_length,
+    );
+
+    if written > 0 && written <= output_buffer_length {
+        protocol::note_decompressed(slice::from_raw_parts(output_buffer, written));
+    }
+
+    written
+}
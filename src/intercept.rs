@@ -0,0 +1,138 @@
+//! Inspection of raw datagrams before ENet processes them.
+
+use crate::addr;
+use crate::checksum;
+use crate::protocol;
+use crate::scope;
+
+use enet_sys::{ENetBuffer, ENetEvent, ENetHost};
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::net::SocketAddr;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+pub trait Interceptor {
+    /// Inspect a datagram received by the host before ENet processes it.
+    ///
+    /// This allows answering non-ENet traffic, such as LAN discovery or server list queries, on the same port.
+    fn intercept(&mut self, datagram: &Datagram) -> Action;
+}
+
+impl<F> Interceptor for F
+where
+    F: FnMut(&Datagram) -> Action,
+{
+    fn intercept(&mut self, datagram: &Datagram) -> Action {
+        self(datagram)
+    }
+}
+
+/// What to do with an intercepted datagram.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// The datagram was handled by the interceptor and is not processed by ENet.
+    Consume,
+    /// The datagram is discarded without being processed, e.g. because it comes from a banned address.
+    ///
+    /// Unlike consumed datagrams, ignored ones are counted by [`Host::ignored_datagrams`](crate::host::Host::ignored_datagrams).
+    Ignore,
+    /// The datagram is processed by ENet as usual.
+    Pass,
+}
+
+/// A raw datagram received by a host.
+pub struct Datagram {
+    host: *mut ENetHost,
+}
+
+impl Datagram {
+    /// Address the datagram was received from.
+    pub fn addr(&self) -> SocketAddr {
+        unsafe { addr::from_enet(&(*self.host).receivedAddress) }
+    }
+
+    /// Contents of the datagram.
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let host = &*self.host;
+            slice::from_raw_parts(host.receivedData, host.receivedDataLength)
+        }
+    }
+
+    /// Sends a datagram back to the address this one was received from, using the socket of the host.
+    pub fn reply(&self, data: &[u8]) -> Result<(), io::Error> {
+        let buffer = ENetBuffer {
+            data: data.as_ptr() as *mut _,
+            dataLength: data.len(),
+        };
+
+        let ret = unsafe {
+            let host = &*self.host;
+            enet_sys::enet_socket_send(host.socket, &host.receivedAddress, &buffer, 1)
+        };
+
+        match ret {
+            ret if ret < 0 => Err(io::Error::last_os_error()),
+            0 => Err(io::ErrorKind::WouldBlock.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Debug for Datagram {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Datagram")
+            .field("addr", &self.addr())
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+pub(crate) struct InterceptCtx {
+    pub(crate) interceptor: Box<dyn Interceptor>,
+    pub(crate) panic: Option<Box<dyn Any + Send>>,
+    pub(crate) ignored: u64,
+}
+
+thread_local! {
+    static CURRENT: Cell<*mut InterceptCtx> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Makes the intercept context available to the callback until the returned guard is dropped.
+pub(crate) fn enter(ctx: *mut InterceptCtx) -> scope::Scope<InterceptCtx> {
+    scope::enter(&CURRENT, ctx)
+}
+
+/// Installed on every host, to notice disconnects of foreign hosts and verify checksums as well.
+pub(crate) unsafe extern "C" fn intercept(host: *mut ENetHost, _event: *mut ENetEvent) -> i32 {
+    checksum::save_received(host);
+
+    let ctx = scope::get(&CURRENT);
+    if !ctx.is_null() {
+        let ctx = &mut *ctx;
+        let datagram = Datagram { host };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| ctx.interceptor.intercept(&datagram)));
+
+        match result {
+            Ok(Action::Consume) => return 1,
+            Ok(Action::Ignore) => {
+                ctx.ignored += 1;
+                return 1;
+            }
+            Ok(Action::Pass) => {}
+            Err(err) => {
+                ctx.panic = Some(err);
+                return -1;
+            }
+        }
+    }
+
+    protocol::note_received(host);
+
+    0
+}
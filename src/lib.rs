@@ -1,21 +1,39 @@
 //! benet is a crate providing convenient and idiomatic Rust bindings to the ENet library.
 //!
 //! For an explanation of what ENet is and what is it for, please see the project's [homepage](http://enet.bespin.org).
+//!
+//! # IPv6
+//!
+//! The bundled ENet is patched for IPv6. Hosts use dual-stack sockets, so they can talk to IPv4 and IPv6 peers alike.
+//! Addresses are passed and reported as [`SocketAddr`](std::net::SocketAddr), IPv4 peers are reported with IPv4 addresses.
 
+#[cfg(all(unix, feature = "tokio"))]
+pub mod async_host;
+pub mod channel;
+pub mod checksum;
+#[cfg(feature = "serde")]
+pub mod codec;
 pub mod compress;
 pub mod error;
 pub mod event;
+pub mod handle;
 pub mod host;
+pub mod intercept;
 pub mod packet;
 pub mod peer;
 
+mod addr;
 mod init;
+mod protocol;
+mod scope;
 
+pub use crate::channel::{Channel, Delivery};
 pub use crate::error::Error;
-pub use crate::event::{Event, EventKind};
+pub use crate::event::{DisconnectReason, Event, EventHandler, EventKind, OwnedEvent};
+pub use crate::handle::HostHandle;
 pub use crate::host::Host;
-pub use crate::packet::{Flags as PacketFlags, Packet};
-pub use crate::peer::{Peer, PeerInfo, PeerMut};
+pub use crate::packet::{Flags as PacketFlags, Packet, PacketReader, PacketWriter, ReadError};
+pub use crate::peer::{Peer, PeerId, PeerInfo, PeerMut, PeerState};
 
 /// Returns the linked version of the ENet library.
 pub fn linked_version() -> u32 {
@@ -1,10 +1,24 @@
+mod reader;
+mod writer;
+
+pub use self::reader::{PacketReader, ReadError};
+pub use self::writer::PacketWriter;
+
+#[cfg(feature = "serde")]
+use crate::codec::{Bincode, Codec};
 use crate::error::Error;
 use crate::init::InitGuard;
 
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
 use enet_sys::ENetPacket;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Formatter};
 use std::mem;
+use std::ptr;
 use std::slice;
+use std::sync::Arc;
 
 pub struct Packet {
     packet: *mut ENetPacket,
@@ -25,6 +39,10 @@ impl Packet {
         }
     }
 
+    pub(crate) fn as_raw(&self) -> *mut ENetPacket {
+        self.packet
+    }
+
     pub(crate) unsafe fn into_raw(self) -> *mut ENetPacket {
         let packet = self.packet;
         mem::forget(self);
@@ -36,10 +54,56 @@ impl Packet {
     ///
     /// Special care is taken not to copy the data vector by managing the memory manually when passing it to ENet.
     pub fn new(data: Vec<u8>, channel_id: u8, flags: Flags) -> Result<Self, Error> {
+        Self::with_owner(Owner::Vec(data), channel_id, flags)
+    }
+
+    /// Creates a new packet sharing its data with other owners of the buffer, without copying it.
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes(data: Bytes, channel_id: u8, flags: Flags) -> Result<Self, Error> {
+        Self::with_owner(Owner::Bytes(data), channel_id, flags)
+    }
+
+    /// Creates a new packet sharing its data with other owners of the buffer, without copying it.
+    pub fn from_shared(data: Arc<[u8]>, channel_id: u8, flags: Flags) -> Result<Self, Error> {
+        Self::with_owner(Owner::Shared(data), channel_id, flags)
+    }
+
+    /// Creates a new packet referencing static data, without copying it.
+    pub fn from_static(data: &'static [u8], channel_id: u8, flags: Flags) -> Result<Self, Error> {
+        Self::with_owner(Owner::Static(data), channel_id, flags)
+    }
+
+    /// Creates a new packet containing a message serialized with the [`Bincode`] codec.
+    #[cfg(feature = "serde")]
+    pub fn from_message<M>(message: &M, channel_id: u8, flags: Flags) -> Result<Self, Error>
+    where
+        M: Serialize + ?Sized,
+    {
+        Self::from_message_with(&Bincode, message, channel_id, flags)
+    }
+
+    /// Creates a new packet containing a message serialized with the given codec.
+    #[cfg(feature = "serde")]
+    pub fn from_message_with<C, M>(
+        codec: &C,
+        message: &M,
+        channel_id: u8,
+        flags: Flags,
+    ) -> Result<Self, Error>
+    where
+        C: Codec,
+        M: Serialize + ?Sized,
+    {
+        Self::new(codec.encode(message)?, channel_id, flags)
+    }
+
+    fn with_owner(owner: Owner, channel_id: u8, flags: Flags) -> Result<Self, Error> {
         let guard = InitGuard::new()?;
+        let data = owner.data();
+
         let packet = unsafe {
             enet_sys::enet_packet_create(
-                data.as_slice().as_ptr() as *const _,
+                data.as_ptr() as *const _,
                 data.len(),
                 flags.flags | enet_sys::_ENetPacketFlag_ENET_PACKET_FLAG_NO_ALLOCATE,
             )
@@ -49,12 +113,11 @@ impl Packet {
             return Err(Error::Unknown);
         }
 
+        // The data is released by dropping the owner once ENet destroys the packet.
         let packet = unsafe { &mut *packet };
-        packet.userData = data.capacity() as *mut _;
+        packet.userData = Box::into_raw(Box::new(owner)) as *mut _;
         packet.freeCallback = Some(free_callback);
 
-        mem::forget(data);
-
         Ok(Self {
             packet,
             channel_id,
@@ -62,6 +125,25 @@ impl Packet {
         })
     }
 
+    /// Deserializes a message sent with [`Packet::from_message`].
+    #[cfg(feature = "serde")]
+    pub fn decode<'de, T>(&'de self) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        self.decode_with(&Bincode)
+    }
+
+    /// Deserializes a message using the given codec.
+    #[cfg(feature = "serde")]
+    pub fn decode_with<'de, C, T>(&'de self, codec: &C) -> Result<T, Error>
+    where
+        C: Codec,
+        T: Deserialize<'de>,
+    {
+        codec.decode(self.data())
+    }
+
     /// Returns flags that the packet was created with.
     pub fn flags(&self) -> Flags {
         Flags {
@@ -79,10 +161,122 @@ impl Packet {
         unsafe { slice::from_raw_parts(packet.data, packet.dataLength) }
     }
 
+    /// Returns a mutable reference to data associated with this packet.
+    ///
+    /// Data shared with other owners is copied first.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.make_unshared();
+
+        let packet = unsafe { &mut *self.packet };
+        if packet.dataLength == 0 {
+            return &mut [];
+        }
+
+        unsafe { slice::from_raw_parts_mut(packet.data, packet.dataLength) }
+    }
+
+    /// Resizes the data of this packet, filling any new space with zeroes.
+    ///
+    /// Data shared with other owners is copied first.
+    pub fn resize(&mut self, len: usize) -> Result<(), Error> {
+        self.make_unshared();
+
+        unsafe {
+            match owner_mut(self.packet) {
+                Some(Owner::Vec(data)) => {
+                    data.resize(len, 0);
+
+                    let packet = &mut *self.packet;
+                    packet.data = data.as_mut_ptr();
+                    packet.dataLength = data.len();
+                }
+                Some(_) => unreachable!(),
+                None => {
+                    // Data allocated by ENet, which leaves any new space uninitialized.
+                    let old_len = (*self.packet).dataLength;
+                    if enet_sys::enet_packet_resize(self.packet, len) < 0 {
+                        return Err(Error::Unknown);
+                    }
+
+                    if len > old_len {
+                        ptr::write_bytes((*self.packet).data.add(old_len), 0, len - old_len);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts this packet into its data.
+    ///
+    /// The data isn't copied if the packet was created from a vector which isn't shared with any clones.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.make_unshared();
+
+        match unsafe { owner_mut(self.packet) } {
+            Some(Owner::Vec(data)) => mem::take(data),
+            _ => self.data().to_vec(),
+        }
+    }
+
     /// Returns the channel associated with this packet.
     pub fn channel_id(&self) -> u8 {
         self.channel_id
     }
+
+    /// Sets the channel the packet is sent on.
+    pub fn set_channel_id(&mut self, channel_id: u8) {
+        self.channel_id = channel_id;
+    }
+
+    /// Copies data shared with other owners, so that it can be modified.
+    fn make_unshared(&mut self) {
+        let owner = match unsafe { owner_mut(self.packet) } {
+            Some(Owner::Vec(_)) | None => return,
+            Some(owner) => owner,
+        };
+
+        let data = match mem::replace(owner, Owner::Vec(Vec::new())) {
+            // The last clone of a vector takes it back without copying.
+            Owner::SharedVec(data) => Arc::try_unwrap(data).unwrap_or_else(|data| data.to_vec()),
+            shared => shared.data().to_vec(),
+        };
+        *owner = Owner::Vec(data);
+
+        let data = owner.data();
+        unsafe {
+            (*self.packet).data = data.as_ptr() as *mut _;
+        }
+    }
+}
+
+// Packets are never shared with ENet while owned by this type.
+unsafe impl Send for Packet {}
+
+/// Creates a packet with the same data, channel and flags.
+///
+/// The data is shared with the clone rather than copied, except for received packets whose data was allocated by
+/// ENet. A vector is moved behind a reference count the first time its packet is cloned, which doesn't move the
+/// vector's buffer. The ENet packet itself is never shared, since its reference count isn't thread-safe.
+impl Clone for Packet {
+    fn clone(&self) -> Self {
+        let flags = Flags {
+            flags: unsafe { (*self.packet).flags }
+                & !(enet_sys::_ENetPacketFlag_ENET_PACKET_FLAG_NO_ALLOCATE
+                    | enet_sys::_ENetPacketFlag_ENET_PACKET_FLAG_SENT),
+        };
+
+        // Packets aren't `Sync`, so nothing else accesses the owner while it's replaced. Data borrowed from this
+        // packet stays valid, since it points into the buffer rather than the owner.
+        let owner = match unsafe { owner_mut(self.packet) } {
+            Some(owner) => owner.share(),
+            None => Owner::Vec(self.data().to_vec()),
+        };
+
+        // The library is known to be initialized, so this fails only if allocation does.
+        Self::with_owner(owner, self.channel_id, flags).expect("failed to allocate packet")
+    }
 }
 
 impl Drop for Packet {
@@ -170,10 +364,61 @@ impl Debug for Flags {
     }
 }
 
+/// Returns the owner of the data of a packet, unless the data was allocated by ENet.
+unsafe fn owner_mut<'a>(packet: *mut ENetPacket) -> Option<&'a mut Owner> {
+    let packet = &*packet;
+    match packet.freeCallback {
+        Some(_) => (packet.userData as *mut Owner).as_mut(),
+        None => None,
+    }
+}
+
+/// Buffer holding the data of a packet which wasn't allocated by ENet.
+enum Owner {
+    Vec(Vec<u8>),
+    #[cfg(feature = "bytes")]
+    Bytes(Bytes),
+    Shared(Arc<[u8]>),
+    /// Vector of a packet which has been cloned.
+    SharedVec(Arc<Vec<u8>>),
+    Static(&'static [u8]),
+}
+
+impl Owner {
+    fn data(&self) -> &[u8] {
+        match self {
+            Self::Vec(data) => data,
+            #[cfg(feature = "bytes")]
+            Self::Bytes(data) => data,
+            Self::Shared(data) => data,
+            Self::SharedVec(data) => data,
+            Self::Static(data) => data,
+        }
+    }
+
+    /// Returns an owner sharing the same buffer, moving a vector behind a reference count first.
+    fn share(&mut self) -> Self {
+        if let Self::Vec(data) = self {
+            *self = Self::SharedVec(Arc::new(mem::take(data)));
+        }
+
+        match self {
+            Self::Vec(_) => unreachable!(),
+            #[cfg(feature = "bytes")]
+            Self::Bytes(data) => Self::Bytes(data.clone()),
+            Self::Shared(data) => Self::Shared(data.clone()),
+            Self::SharedVec(data) => Self::SharedVec(data.clone()),
+            Self::Static(data) => Self::Static(data),
+        }
+    }
+}
+
 extern "C" fn free_callback(packet: *mut ENetPacket) {
     unsafe {
-        let packet = &*packet;
+        let packet = &mut *packet;
 
-        Vec::<u8>::from_raw_parts(packet.data, packet.dataLength, packet.userData as usize);
+        // Drop the owner.
+        let _ = Box::from_raw(packet.userData as *mut Owner);
+        packet.userData = ptr::null_mut();
     }
 }
@@ -0,0 +1,164 @@
+use crate::packet::Packet;
+
+use std::convert::TryInto;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+
+/// Reads the data of a packet, the counterpart of [`PacketWriter`](crate::packet::PacketWriter).
+///
+/// All reads are bounds-checked and leave the reader untouched on failure.
+#[derive(Clone, Debug)]
+pub struct PacketReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PacketReader<'a> {
+    /// Creates a reader starting at the beginning of the packet data.
+    pub fn new(packet: &'a Packet) -> Self {
+        Self::from_slice(packet.data())
+    }
+
+    /// Creates a reader over arbitrary data.
+    pub fn from_slice(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, ReadError> {
+        self.read_array().map(u8::from_le_bytes)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, ReadError> {
+        self.read_array().map(u16::from_le_bytes)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, ReadError> {
+        self.read_array().map(u32::from_le_bytes)
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, ReadError> {
+        self.read_array().map(u64::from_le_bytes)
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, ReadError> {
+        self.read_array().map(i8::from_le_bytes)
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16, ReadError> {
+        self.read_array().map(i16::from_le_bytes)
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, ReadError> {
+        self.read_array().map(i32::from_le_bytes)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, ReadError> {
+        self.read_array().map(i64::from_le_bytes)
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, ReadError> {
+        self.read_array().map(f32::from_le_bytes)
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64, ReadError> {
+        self.read_array().map(f64::from_le_bytes)
+    }
+
+    /// Reads an unsigned integer written by [`PacketWriter::write_varint`](crate::packet::PacketWriter::write_varint).
+    pub fn read_varint(&mut self) -> Result<u64, ReadError> {
+        let mut value = 0u64;
+
+        for (i, byte) in self.remaining().iter().enumerate().take(10) {
+            let bits = (*byte & 0x7F) as u64;
+
+            // The tenth byte may only hold the most significant bit.
+            if i == 9 && bits > 1 {
+                return Err(ReadError::InvalidVarint);
+            }
+
+            value |= bits << (i * 7);
+
+            if *byte & 0x80 == 0 {
+                self.position += i + 1;
+                return Ok(value);
+            }
+        }
+
+        if self.remaining().len() >= 10 {
+            Err(ReadError::InvalidVarint)
+        } else {
+            Err(ReadError::UnexpectedEnd)
+        }
+    }
+
+    /// Reads a signed integer written by [`PacketWriter::write_varint_signed`](crate::packet::PacketWriter::write_varint_signed).
+    pub fn read_varint_signed(&mut self) -> Result<i64, ReadError> {
+        let value = self.read_varint()?;
+
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Reads the given number of bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ReadError> {
+        let remaining = self.remaining();
+        if remaining.len() < len {
+            return Err(ReadError::UnexpectedEnd);
+        }
+
+        self.position += len;
+
+        Ok(&remaining[..len])
+    }
+
+    /// Returns the data that hasn't been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns whether all data has been read.
+    pub fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ReadError> {
+        self.read_bytes(N).map(|bytes| bytes.try_into().unwrap())
+    }
+}
+
+impl Read for PacketReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.remaining().len());
+
+        buf[..len].copy_from_slice(&self.remaining()[..len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+/// Error returned when reading from a [`PacketReader`] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// The packet ended before the value was read completely.
+    UnexpectedEnd,
+    /// A varint doesn't fit into 64 bits.
+    InvalidVarint,
+}
+
+impl error::Error for ReadError {}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self {
+            Self::UnexpectedEnd => write!(f, "Unexpected end of packet"),
+            Self::InvalidVarint => write!(f, "Invalid varint"),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crate::packet::{Flags, Packet};
+
+use std::io::{self, Write};
+
+/// Builds the data of a packet, writing numbers in little-endian byte order.
+///
+/// Since this type implements [`Write`], anything able to serialize into a writer can be used with it as well.
+#[derive(Clone, Debug)]
+pub struct PacketWriter {
+    data: Vec<u8>,
+    channel_id: u8,
+    flags: Flags,
+}
+
+impl PacketWriter {
+    /// Creates a writer for a packet to be sent on the given channel.
+    pub fn new(channel_id: u8, flags: Flags) -> Self {
+        Self::with_capacity(0, channel_id, flags)
+    }
+
+    /// Creates a writer for a packet to be sent on the given channel, preallocating space for `capacity` bytes.
+    pub fn with_capacity(capacity: usize, channel_id: u8, flags: Flags) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            channel_id,
+            flags,
+        }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i8(&mut self, value: i8) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i16(&mut self, value: i16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes an unsigned integer as a LEB128 varint, taking between 1 and 10 bytes.
+    pub fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.data.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.data.push(value as u8);
+    }
+
+    /// Writes a signed integer as a zigzag-encoded LEB128 varint, so that small negative values take few bytes as well.
+    pub fn write_varint_signed(&mut self, value: i64) {
+        self.write_varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.data.extend_from_slice(value);
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the data written so far.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Creates a packet out of the written data, without copying it.
+    pub fn finish(self) -> Result<Packet, Error> {
+        Packet::new(self.data, self.channel_id, self.flags)
+    }
+}
+
+impl Write for PacketWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.data.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,15 +1,25 @@
+use crate::addr;
+use crate::channel::Channel;
+use crate::checksum;
+#[cfg(feature = "serde")]
+use crate::codec::Codec;
 use crate::host::Host;
 use crate::init::InitGuard;
+#[cfg(feature = "serde")]
+use crate::packet::Flags;
 use crate::packet::Packet;
 use crate::Error;
 
 use enet_sys::ENetPeer;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::fmt::{self, Debug, Formatter};
 use std::marker::PhantomData;
-use std::net::SocketAddrV4;
+use std::net::SocketAddr;
 use std::ptr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub const PACKET_LOSS_SCALE: u32 = enet_sys::ENET_PEER_PACKET_LOSS_SCALE;
 pub const PACKET_THROTTLE_INTERVAL: Duration =
@@ -40,13 +50,18 @@ impl<T> Peer<'_, T> {
 
     /// Returns a reference to data associated with this peer.
     ///
-    /// The data is created default-initialized for the first time a peer is returned from a [Host](crate::host::Host).
+    /// The data is created by the peer data factory of the host, see [`HostBuilder::with_peer_data_factory`](crate::host::HostBuilder::with_peer_data_factory).
     pub fn data(&self) -> &T {
-        unsafe { &*((*self.peer).data as *const T) }
+        unsafe { &(*((*self.peer).data as *const PeerData<T>)).value }
+    }
+
+    /// Returns the identifier of this peer.
+    pub fn id(&self) -> PeerId {
+        unsafe { PeerId::from_raw::<T>(self.peer) }
     }
 
     /// Returns information about this peer.
-    pub fn info(&self) -> PeerInfo {
+    pub fn info(&self) -> PeerInfo<'_> {
         PeerInfo {
             peer: unsafe { &*self.peer },
         }
@@ -56,6 +71,7 @@ impl<T> Peer<'_, T> {
 impl<T: Debug> Debug for Peer<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Peer")
+            .field("id", &self.id())
             .field("data", self.data())
             .field("info", &self.info())
             .finish()
@@ -85,7 +101,12 @@ impl<T> PeerMut<'_, T> {
     /// An [`EventKind::Disconnect`](crate::event::EventKind::Disconnect) will be generated by [`Host::service`](crate::host::Host::service) once the disconnection is complete.
     pub fn disconnect(self, data: u32) {
         unsafe {
+            data_mut::<T>(self.peer).disconnect_data = Some(data);
+
+            let _scope = checksum::enter_host((*self.peer).host);
             enet_sys::enet_peer_disconnect(self.peer, data);
+
+            self.drop_data_if_reset();
         }
     }
 
@@ -94,7 +115,19 @@ impl<T> PeerMut<'_, T> {
     /// An [`EventKind::Disconnect`](crate::event::EventKind::Disconnect) will be generated by [`Host::service`](`crate::host::Host::service`) once the disconnection is complete.
     pub fn disconnect_later(self, data: u32) {
         unsafe {
+            data_mut::<T>(self.peer).disconnect_data = Some(data);
+
+            let _scope = checksum::enter_host((*self.peer).host);
             enet_sys::enet_peer_disconnect_later(self.peer, data);
+
+            self.drop_data_if_reset();
+        }
+    }
+
+    /// Peers that aren't fully connected are reset immediately by ENet without generating a disconnect event.
+    unsafe fn drop_data_if_reset(&self) {
+        if (*self.peer).state == enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECTED {
+            drop_data::<T>(self.peer);
         }
     }
 
@@ -104,6 +137,8 @@ impl<T> PeerMut<'_, T> {
     pub fn disconnect_now(self, data: u32) {
         unsafe {
             drop_data::<T>(self.peer);
+
+            let _scope = checksum::enter_host((*self.peer).host);
             enet_sys::enet_peer_disconnect_now(self.peer, data);
         }
     }
@@ -119,7 +154,50 @@ impl<T> PeerMut<'_, T> {
     }
 
     /// Attempts to dequeue a packet from a peer.
+    ///
+    /// Packets are returned in the order they were received, including those left queued by a host built with
+    /// [`HostBuilder::queued_receive`](crate::host::HostBuilder::queued_receive).
     pub fn receive(&mut self) -> Option<Packet> {
+        let data = unsafe { data_mut::<T>(self.peer) };
+        data.notified = false;
+
+        data.take_queued(0).or_else(|| self.dequeue())
+    }
+
+    /// Attempts to dequeue a packet received on the given channel from a peer.
+    ///
+    /// Packets received on other channels in the meantime stay queued.
+    pub fn receive_on(&mut self, channel_id: u8) -> Option<Packet> {
+        let data = unsafe { data_mut::<T>(self.peer) };
+        data.notified = false;
+
+        if let Some(index) = data
+            .queue
+            .iter()
+            .position(|packet| packet.channel_id() == channel_id)
+        {
+            return data.take_queued(index);
+        }
+
+        while let Some(packet) = self.dequeue() {
+            if packet.channel_id() == channel_id {
+                return Some(packet);
+            }
+
+            data.enqueue(packet);
+        }
+
+        None
+    }
+
+    /// Returns the number of packets queued on the peer that ENet has already handed over.
+    ///
+    /// Packets not yet dispatched by [`Host::service`](crate::host::Host::service) aren't counted, but can be received all the same.
+    pub fn queued_packets(&self) -> usize {
+        unsafe { data::<T>(self.peer) }.queue.len()
+    }
+
+    fn dequeue(&mut self) -> Option<Packet> {
         let mut channel_id = 0;
 
         let packet = unsafe { enet_sys::enet_peer_receive(self.peer, &mut channel_id as *mut _) };
@@ -127,6 +205,10 @@ impl<T> PeerMut<'_, T> {
             return None;
         }
 
+        unsafe {
+            count_received(self.peer, (*packet).dataLength);
+        }
+
         Some(unsafe {
             // This unwrap will never fail because the existence of a peer implies the library has already been initialized.
             Packet::from_raw(packet, channel_id, InitGuard::new().unwrap())
@@ -143,18 +225,68 @@ impl<T> PeerMut<'_, T> {
         }
     }
 
+    /// Resets the traffic statistics returned by [`PeerInfo::stats`] to zero.
+    pub fn reset_stats(&mut self) {
+        if let Some(header) = unsafe { header_mut(self.peer) } {
+            header.stats = PeerStats::default();
+        }
+    }
+
     /// Queues a packet to be sent.
     pub fn send(&mut self, packet: Packet) -> Result<(), Error> {
         let ret =
-            unsafe { enet_sys::enet_peer_send(self.peer, packet.channel_id(), packet.into_raw()) };
+            unsafe { enet_sys::enet_peer_send(self.peer, packet.channel_id(), packet.as_raw()) };
 
         if ret < 0 {
+            // ENet didn't take the packet, so it's destroyed here.
             return Err(Error::Unknown);
         }
 
+        unsafe {
+            count_sent(self.peer, packet.data().len());
+            packet.into_raw();
+        }
+
         Ok(())
     }
 
+    /// Queues data to be sent on a channel, using the flags of its delivery policy.
+    pub fn send_on(&mut self, channel: Channel, data: Vec<u8>) -> Result<(), Error> {
+        self.send(Packet::new(data, channel.id(), channel.delivery().flags())?)
+    }
+
+    /// Serializes a message with the [`Bincode`](crate::codec::Bincode) codec and queues it to be sent.
+    #[cfg(feature = "serde")]
+    pub fn send_message<M>(
+        &mut self,
+        message: &M,
+        channel_id: u8,
+        flags: Flags,
+    ) -> Result<(), Error>
+    where
+        M: Serialize + ?Sized,
+    {
+        self.send(Packet::from_message(message, channel_id, flags)?)
+    }
+
+    /// Serializes a message with the given codec and queues it to be sent.
+    #[cfg(feature = "serde")]
+    pub fn send_message_with<C, M>(
+        &mut self,
+        codec: &C,
+        message: &M,
+        channel_id: u8,
+        flags: Flags,
+    ) -> Result<(), Error>
+    where
+        C: Codec,
+        M: Serialize + ?Sized,
+    {
+        self.send(Packet::from_message_with(
+            codec, message, channel_id, flags,
+        )?)
+    }
+
     /// Configures throttle parameter for a peer.
     ///
     /// Unreliable packets are dropped by ENet in response to the varying conditions of the Internet connection to the peer.
@@ -225,20 +357,25 @@ impl<T> PeerMut<'_, T> {
 impl<T> PeerMut<'_, T> {
     /// Returns a reference to data associated with this peer.
     ///
-    /// The data is created default-initialized for the first time a peer is returned from a [`Host`](crate::host::Host).
+    /// The data is created by the peer data factory of the host, see [`HostBuilder::with_peer_data_factory`](crate::host::HostBuilder::with_peer_data_factory).
     pub fn data(&self) -> &T {
-        unsafe { &*((*self.peer).data as *const T) }
+        unsafe { &(*((*self.peer).data as *const PeerData<T>)).value }
     }
 
     /// Returns a mutable reference to data associated with this peer.
     ///
-    /// The data is created default-initialized for the first time a peer is returned from a [`Host`](crate::host::Host).
+    /// The data is created by the peer data factory of the host, see [`HostBuilder::with_peer_data_factory`](crate::host::HostBuilder::with_peer_data_factory).
     pub fn data_mut(&mut self) -> &mut T {
-        unsafe { &mut *((*self.peer).data as *mut T) }
+        unsafe { &mut (*((*self.peer).data as *mut PeerData<T>)).value }
+    }
+
+    /// Returns the identifier of this peer.
+    pub fn id(&self) -> PeerId {
+        unsafe { PeerId::from_raw::<T>(self.peer) }
     }
 
     /// Returns information about this peer.
-    pub fn info(&self) -> PeerInfo {
+    pub fn info(&self) -> PeerInfo<'_> {
         PeerInfo {
             peer: unsafe { &*self.peer },
         }
@@ -258,6 +395,7 @@ impl<T> Drop for PeerMut<'_, T> {
 impl<T: Debug> Debug for PeerMut<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Peer")
+            .field("id", &self.id())
             .field("data", self.data())
             .field("info", &self.info())
             .finish()
@@ -273,22 +411,63 @@ pub struct PeerInfo<'a> {
 impl Debug for PeerInfo<'_> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("PeerInfo")
+            .field("state", &self.state())
             .field("addr", &self.addr())
+            .field("connect_id", &self.connect_id())
+            .field("incoming_peer_id", &self.incoming_peer_id())
+            .field("outgoing_peer_id", &self.outgoing_peer_id())
+            .field("channel_count", &self.channel_count())
             .field("incoming_bandwidth", &self.incoming_bandwidth())
             .field("outgoing_bandwidth", &self.outgoing_bandwidth())
+            .field("stats", &self.stats())
+            .field("packets_lost", &self.packets_lost())
             .field("packet_loss", &self.packet_loss())
             .field("round_trip_time", &self.round_trip_time())
+            .field("round_trip_time_variance", &self.round_trip_time_variance())
+            .field("lowest_round_trip_time", &self.lowest_round_trip_time())
+            .field("packet_throttle", &self.packet_throttle())
+            .field("mtu", &self.mtu())
+            .field("window_size", &self.window_size())
+            .field("reliable_data_in_transit", &self.reliable_data_in_transit())
+            .field("last_send_time", &self.last_send_time())
+            .field("last_receive_time", &self.last_receive_time())
             .finish()
     }
 }
 
 impl PeerInfo<'_> {
+    pub(crate) unsafe fn from_raw(peer: *const ENetPeer) -> Self {
+        Self { peer: &*peer }
+    }
+
+    /// Current state of the connection.
+    pub fn state(&self) -> PeerState {
+        PeerState::from_raw(self.peer.state)
+    }
+
     /// Remote address of the peer.
-    pub fn addr(&self) -> SocketAddrV4 {
-        SocketAddrV4::new(
-            self.peer.address.host.to_ne_bytes().into(),
-            self.peer.address.port,
-        )
+    pub fn addr(&self) -> SocketAddr {
+        addr::from_enet(&self.peer.address)
+    }
+
+    /// Connection ID chosen by ENet when the connection was initiated, zero once the peer is disconnected.
+    pub fn connect_id(&self) -> u32 {
+        self.peer.connectID
+    }
+
+    /// ID the foreign host uses for addressing the local host.
+    pub fn incoming_peer_id(&self) -> u16 {
+        self.peer.incomingPeerID
+    }
+
+    /// ID the local host uses for addressing the foreign host.
+    pub fn outgoing_peer_id(&self) -> u16 {
+        self.peer.outgoingPeerID
+    }
+
+    /// Number of channels allocated for communication with the peer.
+    pub fn channel_count(&self) -> usize {
+        self.peer.channelCount
     }
 
     /// Incoming bandwith in bytes/second.
@@ -301,6 +480,20 @@ impl PeerInfo<'_> {
         self.peer.outgoingBandwidth
     }
 
+    /// Returns a snapshot of the traffic statistics of this peer.
+    ///
+    /// The counters are accumulated since the peer was connected or [`PeerMut::reset_stats`] was last called.
+    pub fn stats(&self) -> PeerStats {
+        unsafe { header(self.peer) }
+            .map(|header| header.stats)
+            .unwrap_or_default()
+    }
+
+    /// Reliable packets sent to the peer that were lost during the current packet loss interval.
+    pub fn packets_lost(&self) -> u32 {
+        self.peer.packetsLost
+    }
+
     /// Mean packet loss of reliable packets as a ratio with respect to the constant [`PACKET_LOSS_SCALE`].
     pub fn packet_loss(&self) -> u32 {
         self.peer.packetLoss
@@ -310,13 +503,244 @@ impl PeerInfo<'_> {
     pub fn round_trip_time(&self) -> Duration {
         Duration::from_millis(self.peer.roundTripTime as u64)
     }
+
+    /// Variance of the mean round trip time.
+    pub fn round_trip_time_variance(&self) -> Duration {
+        Duration::from_millis(self.peer.roundTripTimeVariance as u64)
+    }
+
+    /// Lowest round trip time measured during the current packet throttle interval.
+    pub fn lowest_round_trip_time(&self) -> Duration {
+        Duration::from_millis(self.peer.lowestRoundTripTime as u64)
+    }
+
+    /// Current probability of unreliable packets not being dropped as a ratio with respect to the constant [`PACKET_THROTTLE_SCALE`].
+    ///
+    /// See [`PeerMut::configure_throttle`] for details.
+    pub fn packet_throttle(&self) -> u32 {
+        self.peer.packetThrottle
+    }
+
+    /// Maximum transmission unit in bytes.
+    pub fn mtu(&self) -> u32 {
+        self.peer.mtu
+    }
+
+    /// Size of the reliable packet window in bytes.
+    pub fn window_size(&self) -> u32 {
+        self.peer.windowSize
+    }
+
+    /// Bytes of reliable packets sent to the peer but not yet acknowledged.
+    pub fn reliable_data_in_transit(&self) -> u32 {
+        self.peer.reliableDataInTransit
+    }
+
+    /// Time of the last datagram sent to the peer.
+    pub fn last_send_time(&self) -> Instant {
+        enet_instant(self.peer.lastSendTime)
+    }
+
+    /// Time of the last datagram received from the peer.
+    pub fn last_receive_time(&self) -> Instant {
+        enet_instant(self.peer.lastReceiveTime)
+    }
+}
+
+/// State of the connection to a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PeerState {
+    /// Not connected.
+    Disconnected,
+    /// The local host initiated a connection and waits for it to be acknowledged.
+    Connecting,
+    /// The foreign host initiated a connection, which is being acknowledged.
+    AcknowledgingConnect,
+    /// The connection was acknowledged and waits for the foreign host to verify it.
+    ConnectionPending,
+    /// The connection was verified and waits to be reported by the local host.
+    ConnectionSucceeded,
+    /// Connected.
+    Connected,
+    /// A disconnection was requested to happen once all queued outgoing packets are sent.
+    DisconnectLater,
+    /// A disconnection was requested and waits for the foreign host to acknowledge it.
+    Disconnecting,
+    /// The foreign host requested a disconnection, which is being acknowledged.
+    AcknowledgingDisconnect,
+    /// The connection was lost and waits for the disconnection to be reported by the local host.
+    Zombie,
+}
+
+impl PeerState {
+    fn from_raw(state: enet_sys::ENetPeerState) -> Self {
+        match state {
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECTED => Self::Disconnected,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTING => Self::Connecting,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_ACKNOWLEDGING_CONNECT => {
+                Self::AcknowledgingConnect
+            }
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTION_PENDING => Self::ConnectionPending,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTION_SUCCEEDED => {
+                Self::ConnectionSucceeded
+            }
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTED => Self::Connected,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECT_LATER => Self::DisconnectLater,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECTING => Self::Disconnecting,
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_ACKNOWLEDGING_DISCONNECT => {
+                Self::AcknowledgingDisconnect
+            }
+            enet_sys::_ENetPeerState_ENET_PEER_STATE_ZOMBIE => Self::Zombie,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Converts a timestamp from the ENet clock, which counts milliseconds and wraps around.
+fn enet_instant(time: u32) -> Instant {
+    let now = Instant::now();
+    let elapsed =
+        Duration::from_millis(unsafe { enet_sys::enet_time_get() }.wrapping_sub(time) as u64);
+
+    now.checked_sub(elapsed).unwrap_or(now)
+}
+
+/// Identifier of a peer which, unlike [`Peer`] and [`PeerMut`], doesn't borrow the [`Host`](crate::host::Host).
+///
+/// The peer can be looked up again using [`Host::peer`](crate::host::Host::peer) and [`Host::peer_mut`](crate::host::Host::peer_mut).
+/// An identifier consists of the peer slot and the connection ID chosen by ENet,
+/// so once the peer disconnects, a new connection occupying the same slot is practically never mistaken for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PeerId {
+    pub(crate) index: usize,
+    pub(crate) connect_id: u32,
+}
+
+impl PeerId {
+    /// The peer needs to have data attached.
+    pub(crate) unsafe fn from_raw<T>(peer: *const ENetPeer) -> Self {
+        Self {
+            index: index(peer),
+            connect_id: (*((*peer).data as *const PeerData<T>)).connect_id,
+        }
+    }
+}
+
+/// Traffic statistics of a peer, as returned by [`PeerInfo::stats`].
+///
+/// Only the data of packets is counted, unlike [`Stats`](crate::host::Stats) which includes protocol overhead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PeerStats {
+    /// Total number of bytes sent.
+    pub sent_bytes: u64,
+    /// Total number of packets sent, including broadcasts.
+    pub sent_packets: u64,
+    /// Total number of bytes received.
+    pub received_bytes: u64,
+    /// Total number of packets received.
+    pub received_packets: u64,
+}
+
+/// The part of [`PeerData`] that doesn't depend on `T`, so that it can be accessed through [`header`] and [`header_mut`].
+#[derive(Default)]
+pub(crate) struct PeerHeader {
+    pub(crate) stats: PeerStats,
+    /// Whether a disconnect command of the foreign host was received, see [`protocol::note_disconnect`](crate::protocol::note_disconnect).
+    pub(crate) remote_disconnect: bool,
+}
+
+/// Data attached to every peer known to a host.
+#[repr(C)]
+pub(crate) struct PeerData<T> {
+    // Has to be the first field.
+    pub(crate) header: PeerHeader,
+    /// ENet resets the connect ID when a peer disconnects, so a copy is kept for the disconnect event.
+    pub(crate) connect_id: u32,
+    /// Whether a connect event was generated for the peer.
+    pub(crate) connected: bool,
+    /// Data passed when a disconnection was requested locally.
+    pub(crate) disconnect_data: Option<u32>,
+    /// Packets left queued by a host in queued receive mode, or skipped by [`PeerMut::receive_on`].
+    pub(crate) queue: VecDeque<Packet>,
+    /// Total size of the packets in the queue.
+    pub(crate) queued_bytes: usize,
+    /// Whether the peer was disconnected for exceeding [`Host::max_waiting_data`](crate::host::Host::max_waiting_data) in queued receive mode.
+    pub(crate) queue_full: bool,
+    /// Whether an [`EventKind::DataAvailable`](crate::event::EventKind::DataAvailable) event was generated since packets were last received.
+    pub(crate) notified: bool,
+    pub(crate) value: T,
+}
+
+impl<T> PeerData<T> {
+    pub(crate) fn enqueue(&mut self, packet: Packet) {
+        self.queued_bytes += packet.data().len();
+        self.queue.push_back(packet);
+    }
+
+    pub(crate) fn take_queued(&mut self, index: usize) -> Option<Packet> {
+        let packet = self.queue.remove(index)?;
+        self.queued_bytes -= packet.data().len();
+
+        Some(packet)
+    }
+}
+
+pub(crate) unsafe fn attach_data<T>(peer: *mut ENetPeer, value: T) {
+    let peer = &mut *peer;
+    peer.data = Box::into_raw(Box::new(PeerData {
+        header: PeerHeader::default(),
+        connect_id: peer.connectID,
+        connected: false,
+        disconnect_data: None,
+        queue: VecDeque::new(),
+        queued_bytes: 0,
+        queue_full: false,
+        notified: false,
+        value,
+    })) as *mut _;
+}
+
+/// The peer needs to have data attached.
+pub(crate) unsafe fn data<'a, T>(peer: *const ENetPeer) -> &'a PeerData<T> {
+    &*((*peer).data as *const PeerData<T>)
+}
+
+pub(crate) unsafe fn data_mut<'a, T>(peer: *mut ENetPeer) -> &'a mut PeerData<T> {
+    &mut *((*peer).data as *mut PeerData<T>)
+}
+
+pub(crate) unsafe fn header<'a>(peer: *const ENetPeer) -> Option<&'a PeerHeader> {
+    ((*peer).data as *const PeerHeader).as_ref()
+}
+
+pub(crate) unsafe fn header_mut<'a>(peer: *mut ENetPeer) -> Option<&'a mut PeerHeader> {
+    ((*peer).data as *mut PeerHeader).as_mut()
+}
+
+pub(crate) unsafe fn count_sent(peer: *mut ENetPeer, len: usize) {
+    if let Some(header) = header_mut(peer) {
+        header.stats.sent_bytes += len as u64;
+        header.stats.sent_packets += 1;
+    }
+}
+
+pub(crate) unsafe fn count_received(peer: *mut ENetPeer, len: usize) {
+    if let Some(header) = header_mut(peer) {
+        header.stats.received_bytes += len as u64;
+        header.stats.received_packets += 1;
+    }
 }
 
 pub(crate) unsafe fn drop_data<T>(peer: *mut ENetPeer) {
     let peer = &mut *peer;
     if !peer.data.is_null() {
         // Drop the data.
-        let _ = Box::from_raw(peer.data as *mut T);
+        let _ = Box::from_raw(peer.data as *mut PeerData<T>);
         peer.data = ptr::null_mut();
     }
 }
+
+/// Returns the index of a peer within the peer array of its host.
+pub(crate) unsafe fn index(peer: *const ENetPeer) -> usize {
+    peer.offset_from((*(*peer).host).peers) as usize
+}
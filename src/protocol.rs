@@ -0,0 +1,197 @@
+//! Inspection of the ENet protocol, for details ENet doesn't report itself.
+
+use crate::peer;
+
+use enet_sys::{ENetHost, ENetPeer};
+use std::cell::Cell;
+use std::convert::TryInto;
+use std::slice;
+
+const MAXIMUM_PEER_ID: usize = enet_sys::ENET_PROTOCOL_MAXIMUM_PEER_ID as usize;
+
+const HEADER_FLAG_COMPRESSED: u16 =
+    enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_COMPRESSED as u16;
+const HEADER_FLAG_SENT_TIME: u16 =
+    enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_SENT_TIME as u16;
+const HEADER_FLAG_MASK: u16 = enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_FLAG_MASK as u16;
+const HEADER_SESSION_MASK: u16 =
+    enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_SESSION_MASK as u16;
+const HEADER_SESSION_SHIFT: u16 =
+    enet_sys::_ENetProtocolFlag_ENET_PROTOCOL_HEADER_SESSION_SHIFT as u16;
+
+const COMMAND_MASK: u8 = enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_MASK as u8;
+const COMMAND_COUNT: u8 = enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_COUNT as u8;
+const COMMAND_DISCONNECT: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_DISCONNECT as u8;
+const COMMAND_SEND_RELIABLE: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_RELIABLE as u8;
+const COMMAND_SEND_UNRELIABLE: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNRELIABLE as u8;
+const COMMAND_SEND_FRAGMENT: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_FRAGMENT as u8;
+const COMMAND_SEND_UNSEQUENCED: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNSEQUENCED as u8;
+const COMMAND_SEND_UNRELIABLE_FRAGMENT: u8 =
+    enet_sys::_ENetProtocolCommand_ENET_PROTOCOL_COMMAND_SEND_UNRELIABLE_FRAGMENT as u8;
+
+/// Where the inspection of the datagram currently being received stands.
+#[derive(Clone, Copy)]
+enum Pending {
+    None,
+    /// The datagram is compressed, its commands are looked at once ENet decompressed it.
+    Decompress {
+        peer: *mut ENetPeer,
+        checksum: bool,
+    },
+    /// The datagram contains a disconnect command, which counts once ENet verified its checksum.
+    Checksum {
+        peer: *mut ENetPeer,
+    },
+}
+
+thread_local! {
+    static PENDING: Cell<Pending> = const { Cell::new(Pending::None) };
+}
+
+/// Starts looking for a disconnect command in the datagram received by the host, called from the intercept callback.
+///
+/// ENet passes zero as data for timeouts and resets the peer before reporting either, so a disconnect
+/// of the foreign host can only be told apart while the datagram is received. The datagram is validated
+/// the way `enet_protocol_handle_incoming_commands` does before its commands are looked at.
+///
+/// Compressed datagrams are looked at in [`note_decompressed`] and checksums are left to [`note_checksum`],
+/// so that neither the compressor nor the checksum of the host is ever invoked by this crate on its own.
+pub(crate) unsafe fn note_received(host: *mut ENetHost) {
+    PENDING.with(|pending| pending.set(Pending::None));
+
+    let _ = try_note_received(host);
+}
+
+unsafe fn try_note_received(host: *mut ENetHost) -> Option<()> {
+    let host = &*host;
+    let data = slice::from_raw_parts(host.receivedData, host.receivedDataLength);
+
+    let header = u16::from_be_bytes(data.get(..2)?.try_into().unwrap());
+    let flags = header & HEADER_FLAG_MASK;
+    let session = ((header & HEADER_SESSION_MASK) >> HEADER_SESSION_SHIFT) as u8;
+    let index = (header & !(HEADER_FLAG_MASK | HEADER_SESSION_MASK)) as usize;
+
+    // Datagrams without a peer may only contain connect commands.
+    if index == MAXIMUM_PEER_ID || index >= host.peerCount {
+        return None;
+    }
+
+    let peer = host.peers.add(index);
+    if !accepts_disconnect(host, &*peer, session) {
+        return None;
+    }
+
+    let checksum = host.checksum.is_some();
+    if flags & HEADER_FLAG_COMPRESSED != 0 {
+        PENDING.with(|pending| pending.set(Pending::Decompress { peer, checksum }));
+        return Some(());
+    }
+
+    let mut header_size = if flags & HEADER_FLAG_SENT_TIME != 0 {
+        4
+    } else {
+        2
+    };
+    if checksum {
+        header_size += 4;
+    }
+
+    if contains_disconnect(data.get(header_size..)?) {
+        note_disconnect(peer, checksum);
+    }
+
+    Some(())
+}
+
+/// Looks at the commands of a compressed datagram, called with the output of the decompressor installed by the host.
+pub(crate) unsafe fn note_decompressed(commands: &[u8]) {
+    if let Pending::Decompress { peer, checksum } =
+        PENDING.with(|pending| pending.replace(Pending::None))
+    {
+        if contains_disconnect(commands) {
+            note_disconnect(peer, checksum);
+        }
+    }
+}
+
+/// Completes the inspection once the checksum of the received datagram has been verified by ENet.
+pub(crate) unsafe fn note_checksum(matches: bool) {
+    if let Pending::Checksum { peer } = PENDING.with(|pending| pending.replace(Pending::None)) {
+        // A datagram failing the checksum is dropped by ENet, so it mustn't count either.
+        if matches {
+            note_disconnect(peer, false);
+        }
+    }
+}
+
+/// Marks the peer as disconnected by the foreign host, once the checksum is verified if there is one.
+unsafe fn note_disconnect(peer: *mut ENetPeer, verify_checksum: bool) {
+    if verify_checksum {
+        PENDING.with(|pending| pending.set(Pending::Checksum { peer }));
+    } else if let Some(header) = peer::header_mut(peer) {
+        header.remote_disconnect = true;
+    }
+}
+
+/// Whether a disconnect command received in a datagram for the peer results in a disconnect event.
+unsafe fn accepts_disconnect(host: &ENetHost, peer: &ENetPeer, session: u8) -> bool {
+    let state_reported = matches!(
+        peer.state,
+        enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTING
+            | enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTION_SUCCEEDED
+            | enet_sys::_ENetPeerState_ENET_PEER_STATE_CONNECTED
+            | enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECT_LATER
+            | enet_sys::_ENetPeerState_ENET_PEER_STATE_DISCONNECTING
+    );
+
+    let addr_matches = (host.receivedAddress.host == peer.address.host
+        && host.receivedAddress.port == peer.address.port)
+        || peer.address.host == enet_sys::ENET_HOST_BROADCAST;
+
+    let session_matches =
+        peer.outgoingPeerID as usize >= MAXIMUM_PEER_ID || session == peer.incomingSessionID;
+
+    state_reported && addr_matches && session_matches
+}
+
+/// Whether the commands of a datagram, following its header, contain a disconnect command.
+unsafe fn contains_disconnect(mut commands: &[u8]) -> bool {
+    // Each command starts with a four byte header.
+    while commands.len() >= 4 {
+        let command = commands[0] & COMMAND_MASK;
+        if command >= COMMAND_COUNT {
+            break;
+        }
+
+        let size = enet_sys::enet_protocol_command_size(command);
+        if size == 0 || size > commands.len() {
+            break;
+        }
+
+        if command == COMMAND_DISCONNECT {
+            return true;
+        }
+
+        // Sent packets follow the command they are sent with.
+        let data_length = match command {
+            COMMAND_SEND_RELIABLE => u16::from_be_bytes([commands[4], commands[5]]),
+            COMMAND_SEND_UNRELIABLE
+            | COMMAND_SEND_FRAGMENT
+            | COMMAND_SEND_UNSEQUENCED
+            | COMMAND_SEND_UNRELIABLE_FRAGMENT => u16::from_be_bytes([commands[6], commands[7]]),
+            _ => 0,
+        };
+
+        commands = match commands.get(size + data_length as usize..) {
+            Some(rest) => rest,
+            None => break,
+        };
+    }
+
+    false
+}
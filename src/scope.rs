@@ -0,0 +1,34 @@
+//! Context for ENet callbacks that don't receive one.
+//!
+//! Some ENet callbacks aren't passed any user data, so their context is made available through a thread-local
+//! for the duration of calls into ENet instead.
+
+use std::cell::Cell;
+use std::thread::LocalKey;
+
+/// Thread-local holding the context of the ongoing call into ENet, null outside of one.
+pub(crate) type Current<T> = LocalKey<Cell<*mut T>>;
+
+/// Makes `ctx` the context of `current` until the returned guard is dropped.
+pub(crate) fn enter<T>(current: &'static Current<T>, ctx: *mut T) -> Scope<T> {
+    Scope {
+        current,
+        prev: current.with(|current| current.replace(ctx)),
+    }
+}
+
+/// Returns the context of `current`, which is null if no scope was entered.
+pub(crate) fn get<T>(current: &'static Current<T>) -> *mut T {
+    current.with(|current| current.get())
+}
+
+pub(crate) struct Scope<T: 'static> {
+    current: &'static Current<T>,
+    prev: *mut T,
+}
+
+impl<T> Drop for Scope<T> {
+    fn drop(&mut self) {
+        self.current.with(|current| current.set(self.prev));
+    }
+}
@@ -0,0 +1,35 @@
+use benet::{Delivery, Error, Host};
+
+fn host() -> Host<()> {
+    Host::builder()
+        .channel("chat", Delivery::ReliableOrdered)
+        .channel("movement", Delivery::Unsequenced)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn connect_requires_schema_channels() {
+    let mut host = host();
+
+    assert!(matches!(
+        host.connect(("127.0.0.1", 1), 1, 0, ()),
+        Err(Error::InvalidArgument)
+    ));
+
+    assert!(host.connect(("127.0.0.1", 1), 2, 0, ()).is_ok());
+}
+
+#[test]
+fn channel_limit_covers_schema_channels() {
+    let mut host = host();
+
+    assert!(matches!(
+        host.set_channel_limit(1),
+        Err(Error::InvalidArgument)
+    ));
+    assert_eq!(host.channel_limit(), 2);
+
+    host.set_channel_limit(3).unwrap();
+    assert_eq!(host.channel_limit(), 3);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use benet::checksum::{Checksum, Crc32};
+use benet::compress::InputBuffer;
+use benet::host::ChecksumKind;
+use benet::{EventKind, Host};
+use common::{free_port, pump};
+use std::time::Duration;
+
+/// CRC32 with a flipped bit, so that none of its checksums match.
+struct Broken;
+
+impl Checksum for Broken {
+    fn checksum(&mut self, input_buffers: &[InputBuffer]) -> u32 {
+        Crc32.checksum(input_buffers) ^ 1
+    }
+}
+
+#[test]
+fn mismatched_checksums_are_counted() {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(2)
+        .checksum(ChecksumKind::Crc32)
+        .build()
+        .unwrap();
+
+    let mut broken = Host::<()>::builder()
+        .checksum(ChecksumKind::Custom(Box::new(Broken)))
+        .build()
+        .unwrap();
+    broken.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    let mut server_connects = 0;
+
+    pump(
+        &mut [&mut server, &mut broken],
+        Duration::from_secs(1),
+        |_, events| {
+            for (index, event) in events {
+                if let (0, EventKind::Connect(_)) = (index, event.kind) {
+                    server_connects += 1;
+                }
+            }
+
+            false
+        },
+    );
+
+    assert_eq!(server_connects, 0);
+    assert!(server.checksum_mismatches() > 0);
+    assert_eq!(broken.checksum_mismatches(), 0);
+
+    // A client using the same checksum gets through without adding mismatches.
+    let mismatches = server.checksum_mismatches();
+
+    let mut client = Host::<()>::builder()
+        .checksum(ChecksumKind::Crc32)
+        .build()
+        .unwrap();
+    client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |_, events| {
+            for (index, event) in events {
+                if let (0, EventKind::Connect(_)) = (index, event.kind) {
+                    server_connects += 1;
+                }
+            }
+
+            server_connects > 0
+        },
+    );
+
+    assert_eq!(server_connects, 1);
+    assert_eq!(server.checksum_mismatches(), mismatches);
+}
@@ -0,0 +1,40 @@
+use benet::{Host, OwnedEvent};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+/// Finds a free port, ENet needs it upfront.
+pub fn free_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Services the hosts until `until` returns true or `timeout` elapses, returning whether it did.
+///
+/// `until` is called after each round of servicing with the events of that round, paired with the index of the
+/// host they came from.
+pub fn pump<T>(
+    hosts: &mut [&mut Host<T>],
+    timeout: Duration,
+    mut until: impl FnMut(&mut [&mut Host<T>], Vec<(usize, OwnedEvent)>) -> bool,
+) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        let mut events = Vec::new();
+        for (index, host) in hosts.iter_mut().enumerate() {
+            while let Some(event) = host.service(Duration::ZERO).unwrap() {
+                events.push((index, event.into_owned()));
+            }
+        }
+
+        if until(hosts, events) {
+            return true;
+        }
+
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    false
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use benet::compress::{self, Compressor, InputBuffer, OutputBuffer};
+use benet::host::{ChecksumKind, CompressorKind, HostBuilder};
+use benet::{DisconnectReason, EventKind, Host};
+use common::{free_port, pump};
+use std::io::Write;
+use std::time::Duration;
+
+/// Encodes runs of zeros as a zero followed by the length of the run.
+///
+/// Unlike the range coder, this shrinks even a datagram containing nothing but a disconnect command,
+/// so that ENet sends it compressed.
+struct ZeroRuns;
+
+impl Compressor for ZeroRuns {
+    fn compress(
+        &mut self,
+        input_buffers: &[InputBuffer],
+        output_buffer: &mut OutputBuffer,
+    ) -> Result<(), compress::Error> {
+        let mut zeros = 0u8;
+
+        for &byte in input_buffers.iter().flat_map(|buffer| buffer.as_ref()) {
+            if byte == 0 && zeros < u8::MAX {
+                zeros += 1;
+                continue;
+            }
+
+            if zeros > 0 {
+                output_buffer.write_all(&[0, zeros])?;
+                zeros = 0;
+            }
+
+            if byte == 0 {
+                zeros = 1;
+            } else {
+                output_buffer.write_all(&[byte])?;
+            }
+        }
+
+        if zeros > 0 {
+            output_buffer.write_all(&[0, zeros])?;
+        }
+
+        Ok(())
+    }
+
+    fn decompress(
+        &mut self,
+        input_buffers: &[InputBuffer],
+        output_buffer: &mut OutputBuffer,
+    ) -> Result<(), compress::Error> {
+        let mut input = input_buffers.iter().flat_map(|buffer| buffer.as_ref());
+
+        while let Some(&byte) = input.next() {
+            if byte == 0 {
+                let zeros = *input.next().ok_or(compress::Error)?;
+                output_buffer.write_all(&vec![0; zeros as usize])?;
+            } else {
+                output_buffer.write_all(&[byte])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Connects a client to the server and disconnects it with zero as data, returning the reason reported by the server.
+fn remote_disconnect(
+    configure: fn(HostBuilder<()>) -> HostBuilder<()>,
+) -> Option<DisconnectReason> {
+    let port = free_port();
+
+    let mut server = configure(Host::builder().addr(("127.0.0.1", port)).peer_count(1))
+        .build()
+        .unwrap();
+    let mut client = configure(Host::builder()).build().unwrap();
+    let id = client.connect(("127.0.0.1", port), 1, 0, ()).unwrap().id();
+
+    let mut server_connected = false;
+    let mut client_connected = false;
+    let mut disconnecting = false;
+    let mut reason = None;
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |hosts, events| {
+            for (index, event) in events {
+                match (index, event.kind) {
+                    (0, EventKind::Connect(_)) => server_connected = true,
+                    (0, EventKind::Disconnect(value)) => reason = Some(value),
+                    (1, EventKind::Connect(_)) => client_connected = true,
+                    _ => {}
+                }
+            }
+
+            // Disconnecting before the server reported the connection would make ENet drop it silently.
+            if server_connected && client_connected && !disconnecting {
+                hosts[1].peer_mut(id).unwrap().disconnect(0);
+                disconnecting = true;
+            }
+
+            reason.is_some()
+        },
+    );
+
+    reason
+}
+
+#[test]
+fn remote_disconnect_with_zero_data() {
+    assert_eq!(
+        remote_disconnect(|builder| builder),
+        Some(DisconnectReason::Remote(0))
+    );
+}
+
+#[test]
+fn remote_disconnect_with_compression() {
+    let reason = remote_disconnect(|builder| builder.compressor(CompressorKind::RangeCoder));
+
+    assert_eq!(reason, Some(DisconnectReason::Remote(0)));
+}
+
+#[test]
+fn remote_disconnect_with_compressed_disconnect() {
+    let reason =
+        remote_disconnect(|builder| builder.compressor(CompressorKind::Custom(Box::new(ZeroRuns))));
+
+    assert_eq!(reason, Some(DisconnectReason::Remote(0)));
+}
+
+#[test]
+fn remote_disconnect_with_compressed_disconnect_and_checksum() {
+    let reason = remote_disconnect(|builder| {
+        builder
+            .compressor(CompressorKind::Custom(Box::new(ZeroRuns)))
+            .checksum(ChecksumKind::Crc32)
+    });
+
+    assert_eq!(reason, Some(DisconnectReason::Remote(0)));
+}
+
+#[test]
+fn remote_disconnect_with_checksum() {
+    let reason = remote_disconnect(|builder| builder.checksum(ChecksumKind::Crc32));
+
+    assert_eq!(reason, Some(DisconnectReason::Remote(0)));
+}
+
+#[test]
+fn remote_disconnect_with_compression_and_checksum() {
+    let reason = remote_disconnect(|builder| {
+        builder
+            .compressor(CompressorKind::RangeCoder)
+            .checksum(ChecksumKind::Crc32)
+    });
+
+    assert_eq!(reason, Some(DisconnectReason::Remote(0)));
+}
+
+#[test]
+fn vanished_client_times_out() {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(1)
+        .build()
+        .unwrap();
+    let mut client = Host::<()>::builder().build().unwrap();
+    client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    let connected = pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |hosts, events| {
+            for (index, event) in events {
+                if let (0, EventKind::Connect(_)) = (index, event.kind) {
+                    hosts[0].peer_mut(event.peer).unwrap().set_timeout(
+                        None,
+                        Some(Duration::from_millis(100)),
+                        Some(Duration::from_millis(300)),
+                    );
+
+                    return true;
+                }
+            }
+
+            false
+        },
+    );
+    assert!(connected);
+
+    // Destroying the client host resets its peer without notifying the server.
+    drop(client);
+
+    let mut reason = None;
+
+    pump(&mut [&mut server], Duration::from_secs(5), |_, events| {
+        for (_, event) in events {
+            if let EventKind::Disconnect(value) = event.kind {
+                reason = Some(value);
+            }
+        }
+
+        reason.is_some()
+    });
+
+    assert_eq!(reason, Some(DisconnectReason::Timeout));
+}
+
+/// Connects a client to a server rejecting it with `code`, returning the events seen by the client.
+fn rejected_connection(code: u32) -> Vec<EventKind> {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(1)
+        .accept_filter(move |_, _| Err(code))
+        .build()
+        .unwrap();
+    let mut client = Host::<()>::builder().build().unwrap();
+    client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    let mut server_events = 0;
+    let mut client_events = Vec::new();
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |_, events| {
+            for (index, event) in events {
+                match index {
+                    0 => server_events += 1,
+                    _ => client_events.push(event.kind),
+                }
+            }
+
+            client_events.len() == 2
+        },
+    );
+
+    assert_eq!(server_events, 0);
+
+    client_events
+}
+
+#[test]
+fn rejected_connection_with_code() {
+    // ENet completes the handshake before the filter runs, so the client sees the connection established first.
+    assert!(matches!(
+        rejected_connection(7)[..],
+        [
+            EventKind::Connect(_),
+            EventKind::Disconnect(DisconnectReason::Remote(7))
+        ]
+    ));
+}
+
+#[test]
+fn rejected_connection_without_code() {
+    assert!(matches!(
+        rejected_connection(0)[..],
+        [
+            EventKind::Connect(_),
+            EventKind::Disconnect(DisconnectReason::Remote(0))
+        ]
+    ));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use benet::{EventKind, Host};
+use common::{free_port, pump};
+use std::iter;
+use std::time::Duration;
+
+#[test]
+fn duplicate_peers_are_refused() {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(4)
+        .duplicate_peers(2)
+        .build()
+        .unwrap();
+
+    assert_eq!(server.duplicate_peers(), 2);
+
+    let mut clients = (0..4)
+        .map(|_| {
+            let mut client = Host::<()>::builder().build().unwrap();
+            client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+            client
+        })
+        .collect::<Vec<_>>();
+
+    let mut server_connects = 0;
+    let mut client_connects = 0;
+
+    let mut hosts = iter::once(&mut server)
+        .chain(&mut clients)
+        .collect::<Vec<_>>();
+    pump(&mut hosts, Duration::from_secs(2), |_, events| {
+        for (index, event) in events {
+            match (index, event.kind) {
+                (0, EventKind::Connect(_)) => server_connects += 1,
+                (_, EventKind::Connect(_)) => client_connects += 1,
+                _ => {}
+            }
+        }
+
+        false
+    });
+
+    assert_eq!(server_connects, 2);
+    assert_eq!(client_connects, 2);
+    assert_eq!(server.peers().count(), 2);
+}
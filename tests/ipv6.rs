@@ -0,0 +1,62 @@
+mod common;
+
+use benet::{EventKind, Host};
+use common::{free_port, pump};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// Connects a client to a server listening on `server_addr` and returns the client's address as reported by the server.
+fn connect(server_addr: SocketAddr, connect_addr: SocketAddr) -> Option<SocketAddr> {
+    let mut server = Host::<()>::builder()
+        .addr(server_addr)
+        .peer_count(1)
+        .build()
+        .unwrap();
+    let mut client = Host::<()>::builder().build().unwrap();
+    let id = client.connect(connect_addr, 1, 0, ()).unwrap().id();
+
+    let mut client_addr = None;
+    let mut client_connected = false;
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |hosts, events| {
+            for (index, event) in events {
+                match (index, event.kind) {
+                    (0, EventKind::Connect(_)) => {
+                        client_addr = Some(hosts[0].peer(event.peer).unwrap().info().addr());
+                    }
+                    (1, EventKind::Connect(_)) => client_connected = true,
+                    _ => {}
+                }
+            }
+
+            client_addr.is_some() && client_connected
+        },
+    );
+
+    assert!(client_connected);
+    assert_eq!(client.peer(id).unwrap().info().addr(), connect_addr);
+
+    client_addr
+}
+
+#[test]
+fn connect_over_ipv6() {
+    let addr = SocketAddr::from((Ipv6Addr::LOCALHOST, free_port()));
+
+    let client_addr = connect(addr, addr).unwrap();
+    assert!(client_addr.is_ipv6());
+    assert!(client_addr.ip().is_loopback());
+}
+
+#[test]
+fn dual_stack_server_reports_ipv4_clients() {
+    let port = free_port();
+    let server_addr = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port));
+    let connect_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+    let client_addr = connect(server_addr, connect_addr).unwrap();
+    assert_eq!(client_addr.ip(), connect_addr.ip());
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use benet::{EventKind, Host, Packet, PacketFlags};
+use common::{free_port, pump};
+use std::sync::Arc;
+use std::time::Duration;
+
+static DATA: [u8; 4] = [1, 2, 3, 4];
+
+#[test]
+fn shared_packets_reference_the_buffer() {
+    let data: Arc<[u8]> = Arc::from(&DATA[..]);
+
+    let packet = Packet::from_shared(data.clone(), 0, PacketFlags::default()).unwrap();
+    assert_eq!(packet.data().as_ptr(), data.as_ptr());
+    assert_eq!(Arc::strong_count(&data), 2);
+
+    let clone = packet.clone();
+    assert_eq!(clone.data().as_ptr(), data.as_ptr());
+    assert_eq!(Arc::strong_count(&data), 3);
+
+    drop(packet);
+    drop(clone);
+    assert_eq!(Arc::strong_count(&data), 1);
+}
+
+#[test]
+fn static_packets_reference_the_buffer() {
+    let packet = Packet::from_static(&DATA, 0, PacketFlags::default()).unwrap();
+    assert_eq!(packet.data().as_ptr(), DATA.as_ptr());
+
+    let clone = packet.clone();
+    assert_eq!(clone.data().as_ptr(), DATA.as_ptr());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_packets_reference_the_buffer() {
+    let data = bytes::Bytes::from(DATA.to_vec());
+
+    let packet = Packet::from_bytes(data.clone(), 0, PacketFlags::default()).unwrap();
+    assert_eq!(packet.data().as_ptr(), data.as_ptr());
+
+    let clone = packet.clone();
+    assert_eq!(clone.data().as_ptr(), data.as_ptr());
+
+    drop(packet);
+    drop(clone);
+    assert!(data.is_unique());
+}
+
+#[test]
+fn clone_shares_vector() {
+    let packet = Packet::new(DATA.to_vec(), 3, PacketFlags::default().reliable()).unwrap();
+    let data = packet.data();
+    let ptr = data.as_ptr();
+
+    let clone = packet.clone();
+    assert_eq!(clone.data().as_ptr(), ptr);
+    assert_eq!(clone.channel_id(), 3);
+    assert!(clone.flags().is_reliable());
+
+    // Data borrowed before cloning is still valid.
+    assert_eq!(data, DATA);
+    assert_eq!(packet.data().as_ptr(), ptr);
+
+    drop(packet);
+    assert_eq!(clone.data(), DATA);
+
+    // The last packet takes the vector back without copying it.
+    let vec = clone.into_vec();
+    assert_eq!(vec.as_ptr(), ptr);
+}
+
+#[test]
+fn cloned_vector_is_copied_while_shared() {
+    let packet = Packet::new(DATA.to_vec(), 0, PacketFlags::default()).unwrap();
+    let ptr = packet.data().as_ptr();
+    let clone = packet.clone();
+
+    let vec = packet.into_vec();
+    assert_ne!(vec.as_ptr(), ptr);
+    assert_eq!(vec, DATA);
+    assert_eq!(clone.data().as_ptr(), ptr);
+}
+
+/// Sends `data` over a loopback connection and returns the packet received by ENet.
+fn received(data: &[u8]) -> Packet {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(1)
+        .build()
+        .unwrap();
+    let mut client = Host::<()>::builder().build().unwrap();
+    client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    let mut packet = None;
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |hosts, events| {
+            for (index, event) in events {
+                match (index, event.kind) {
+                    (0, EventKind::Receive(received)) => packet = Some(received),
+                    (1, EventKind::Connect(_)) => {
+                        let sent = Packet::new(data.to_vec(), 0, PacketFlags::default().reliable());
+                        hosts[1]
+                            .peer_mut(event.peer)
+                            .unwrap()
+                            .send(sent.unwrap())
+                            .unwrap();
+                    }
+                    _ => {}
+                }
+            }
+
+            packet.is_some()
+        },
+    );
+
+    packet.unwrap()
+}
+
+/// Grows and shrinks the packet, then checks that it converts into the resized data.
+fn check_resize(mut packet: Packet) {
+    packet.resize(6).unwrap();
+    assert_eq!(packet.data(), [1, 2, 3, 4, 0, 0]);
+
+    packet.data_mut()[5] = 6;
+    packet.resize(8).unwrap();
+    assert_eq!(packet.data(), [1, 2, 3, 4, 0, 6, 0, 0]);
+
+    packet.resize(2).unwrap();
+    assert_eq!(packet.data(), [1, 2]);
+
+    packet.resize(0).unwrap();
+    assert!(packet.data().is_empty());
+
+    packet.resize(3).unwrap();
+    assert_eq!(packet.into_vec(), [0, 0, 0]);
+}
+
+#[test]
+fn resize_vector_packet() {
+    check_resize(Packet::new(DATA.to_vec(), 0, PacketFlags::default()).unwrap());
+
+    let mut packet = Packet::new(DATA.to_vec(), 0, PacketFlags::default()).unwrap();
+    packet.resize(2).unwrap();
+    assert_eq!(packet.into_vec(), [1, 2]);
+}
+
+#[test]
+fn resize_shared_packet() {
+    let data: Arc<[u8]> = Arc::from(&DATA[..]);
+    check_resize(Packet::from_shared(data.clone(), 0, PacketFlags::default()).unwrap());
+
+    assert_eq!(*data, DATA);
+    assert_eq!(Arc::strong_count(&data), 1);
+}
+
+#[test]
+fn resize_cloned_packet() {
+    let packet = Packet::new(DATA.to_vec(), 0, PacketFlags::default()).unwrap();
+    let clone = packet.clone();
+    check_resize(packet);
+
+    assert_eq!(clone.data(), DATA);
+}
+
+#[test]
+fn resize_received_packet() {
+    check_resize(received(&DATA));
+}
+
+#[test]
+fn data_mut_copies_static_data() {
+    let mut packet = Packet::from_static(&DATA, 0, PacketFlags::default()).unwrap();
+    packet.data_mut()[0] = 9;
+
+    assert_eq!(packet.data(), [9, 2, 3, 4]);
+    assert_ne!(packet.data().as_ptr(), DATA.as_ptr());
+    assert_eq!(DATA, [1, 2, 3, 4]);
+}
+
+#[test]
+fn data_mut_copies_shared_data() {
+    let data: Arc<[u8]> = Arc::from(&DATA[..]);
+
+    let mut packet = Packet::from_shared(data.clone(), 0, PacketFlags::default()).unwrap();
+    packet.data_mut()[0] = 9;
+
+    assert_eq!(packet.data(), [9, 2, 3, 4]);
+    assert_eq!(*data, DATA);
+    assert_eq!(Arc::strong_count(&data), 1);
+}
+
+#[test]
+fn data_mut_keeps_unshared_vector() {
+    let mut packet = Packet::new(DATA.to_vec(), 0, PacketFlags::default()).unwrap();
+    let ptr = packet.data().as_ptr();
+
+    // The clone is gone, so the vector is taken back rather than copied.
+    drop(packet.clone());
+    packet.data_mut()[0] = 9;
+    assert_eq!(packet.data().as_ptr(), ptr);
+
+    let vec = packet.into_vec();
+    assert_eq!(vec.as_ptr(), ptr);
+    assert_eq!(vec, [9, 2, 3, 4]);
+}
+
+#[test]
+fn data_mut_modifies_received_packet() {
+    let mut packet = received(&DATA);
+    packet.data_mut()[0] = 9;
+
+    assert_eq!(packet.into_vec(), [9, 2, 3, 4]);
+}
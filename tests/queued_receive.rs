@@ -0,0 +1,66 @@
+mod common;
+
+use benet::host::DISCONNECT_QUEUE_FULL;
+use benet::{DisconnectReason, EventKind, Host, Packet, PacketFlags};
+use common::{free_port, pump};
+use std::time::Duration;
+
+#[test]
+fn max_waiting_data_limits_the_queue() {
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(1)
+        .queued_receive(true)
+        .max_waiting_data(1000)
+        .build()
+        .unwrap();
+
+    let mut client = Host::<()>::builder().build().unwrap();
+    client.connect(("127.0.0.1", port), 1, 0, ()).unwrap();
+
+    let mut server_peer = None;
+    let mut max_queued = 0;
+    let mut reason = None;
+    let mut client_reason = None;
+
+    pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |hosts, events| {
+            // Nothing is ever received from the queue.
+            for (index, event) in events {
+                match (index, event.kind) {
+                    (0, EventKind::Connect(_)) => server_peer = Some(event.peer),
+                    (0, EventKind::Disconnect(value)) => reason = Some(value),
+                    (1, EventKind::Disconnect(value)) => client_reason = Some(value),
+                    (1, EventKind::Connect(_)) => {
+                        let mut peer = hosts[1].peer_mut(event.peer).unwrap();
+                        for _ in 0..20 {
+                            let packet =
+                                Packet::new(vec![0; 100], 0, PacketFlags::default().reliable())
+                                    .unwrap();
+                            peer.send(packet).unwrap();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(peer) = server_peer.and_then(|id| hosts[0].peer_mut(id)) {
+                max_queued = max_queued.max(peer.queued_packets());
+            }
+
+            reason.is_some()
+        },
+    );
+
+    assert_eq!(reason, Some(DisconnectReason::QueueFull));
+    assert_eq!(
+        client_reason,
+        Some(DisconnectReason::Remote(DISCONNECT_QUEUE_FULL))
+    );
+    // Like ENet, packets are queued until the limit is reached, the packet crossing it included.
+    assert_eq!(max_queued, 10);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use benet::{EventKind, Host, Packet, PacketFlags};
+use common::{free_port, pump};
+use std::time::Duration;
+
+#[test]
+fn service_batch_drains_the_socket() {
+    // ENet reads at most 256 datagrams per call.
+    const COUNT: usize = 300;
+
+    let port = free_port();
+
+    let mut server = Host::<()>::builder()
+        .addr(("127.0.0.1", port))
+        .peer_count(1)
+        .build()
+        .unwrap();
+    let mut client = Host::<()>::builder().build().unwrap();
+    let id = client.connect(("127.0.0.1", port), 1, 0, ()).unwrap().id();
+
+    let mut connects = 0;
+    let connected = pump(
+        &mut [&mut server, &mut client],
+        Duration::from_secs(2),
+        |_, events| {
+            connects += events
+                .iter()
+                .filter(|(_, event)| matches!(event.kind, EventKind::Connect(_)))
+                .count();
+
+            connects == 2
+        },
+    );
+    assert!(connected);
+
+    for i in 0..COUNT {
+        let packet = Packet::new(
+            (i as u32).to_le_bytes().to_vec(),
+            0,
+            PacketFlags::default().unsequenced(),
+        )
+        .unwrap();
+        client.peer_mut(id).unwrap().send(packet).unwrap();
+
+        // Flushing each packet on its own keeps ENet from bundling them into a single datagram.
+        client.flush();
+    }
+
+    std::thread::sleep(Duration::from_millis(100));
+
+    let mut events = Vec::new();
+    server.service_batch(Duration::ZERO, &mut events).unwrap();
+
+    let received = events
+        .iter()
+        .filter(|event| matches!(event.kind, EventKind::Receive(_)))
+        .count();
+    assert_eq!(received, COUNT);
+}
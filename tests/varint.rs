@@ -0,0 +1,61 @@
+use benet::{PacketFlags, PacketReader, PacketWriter, ReadError};
+
+fn write(f: impl FnOnce(&mut PacketWriter)) -> Vec<u8> {
+    let mut writer = PacketWriter::new(0, PacketFlags::default());
+    f(&mut writer);
+    writer.data().to_vec()
+}
+
+#[test]
+fn unsigned_round_trip() {
+    for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX - 1, u64::MAX] {
+        let data = write(|writer| writer.write_varint(value));
+
+        let mut reader = PacketReader::from_slice(&data);
+        assert_eq!(reader.read_varint(), Ok(value));
+        assert!(reader.is_empty());
+    }
+
+    assert_eq!(write(|writer| writer.write_varint(0)), [0]);
+    assert_eq!(write(|writer| writer.write_varint(u64::MAX)).len(), 10);
+}
+
+#[test]
+fn signed_round_trip() {
+    for value in [0, 1, -1, 63, -64, 64, -65, i64::MAX, i64::MIN] {
+        let data = write(|writer| writer.write_varint_signed(value));
+
+        let mut reader = PacketReader::from_slice(&data);
+        assert_eq!(reader.read_varint_signed(), Ok(value));
+        assert!(reader.is_empty());
+    }
+
+    // Zigzag encoding keeps small negative values short.
+    assert_eq!(write(|writer| writer.write_varint_signed(-1)), [1]);
+}
+
+#[test]
+fn overflowing_tenth_byte() {
+    let mut data = vec![0xFF; 9];
+    data.push(0x02);
+
+    let mut reader = PacketReader::from_slice(&data);
+    assert_eq!(reader.read_varint(), Err(ReadError::InvalidVarint));
+    assert_eq!(reader.position(), 0);
+
+    // More than ten bytes is invalid as well.
+    let data = [0x80; 11];
+    assert_eq!(
+        PacketReader::from_slice(&data).read_varint(),
+        Err(ReadError::InvalidVarint)
+    );
+}
+
+#[test]
+fn truncated_input() {
+    for data in [&[][..], &[0x80], &[0xFF; 9]] {
+        let mut reader = PacketReader::from_slice(data);
+        assert_eq!(reader.read_varint(), Err(ReadError::UnexpectedEnd));
+        assert_eq!(reader.position(), 0);
+    }
+}